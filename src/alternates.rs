@@ -0,0 +1,232 @@
+use std::{
+    any::Any,
+    borrow::Borrow,
+    fmt,
+    ops::Range,
+    panic::{self, AssertUnwindSafe},
+};
+
+type Implementation<'a, I, T> = Box<dyn Fn(&I) -> T + 'a>;
+
+/// What an implementation returned, or the message it panicked with.
+pub type Outcome<T> = Result<T, String>;
+
+/// Several implementations of the same part, which have to agree on every
+/// input.
+pub struct Alternates<'a, T, I: ?Sized = str> {
+    part: &'static str,
+    implementations: Vec<(&'static str, Implementation<'a, I, T>)>,
+}
+
+/// Inputs which can be cut into parts, so that a divergence can be shrunk to
+/// the fewest parts still showing it.
+pub trait Shrink: ToOwned + fmt::Display {
+    fn parts(&self) -> usize;
+
+    /// The input with the parts in `range` left out, `None` if nothing
+    /// would be left.
+    fn without(&self, range: Range<usize>) -> Option<Self::Owned>;
+}
+
+/// Cut into lines.
+impl Shrink for str {
+    fn parts(&self) -> usize {
+        self.lines().count()
+    }
+
+    fn without(&self, range: Range<usize>) -> Option<String> {
+        let lines = self.lines().collect::<Vec<_>>();
+        let candidate = [&lines[..range.start], &lines[range.end..]].concat();
+
+        (!candidate.is_empty()).then(|| candidate.join("\n"))
+    }
+}
+
+/// Implementations disagreeing, together with the smallest input which
+/// still makes them disagree.
+#[derive(Debug)]
+pub struct Divergence<T> {
+    pub part: &'static str,
+    pub input: String,
+    pub results: Vec<(&'static str, Outcome<T>)>,
+}
+
+impl<T: fmt::Debug> fmt::Display for Divergence<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} implementations disagree on", self.part)?;
+        for line in self.input.lines() {
+            writeln!(f, "\t{line}")?;
+        }
+        for (name, result) in &self.results {
+            match result {
+                Ok(result) => writeln!(f, "{name}:\t{result:?}")?,
+                Err(message) => writeln!(f, "{name}:\tpanicked: {message}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// All implementations finished and returned the same.
+fn agree<T: PartialEq>(results: &[(&'static str, Outcome<T>)]) -> bool {
+    results
+        .iter()
+        .all(|(_, result)| result.is_ok() && *result == results[0].1)
+}
+
+impl<'a, T: PartialEq + fmt::Debug, I: Shrink + ?Sized> Alternates<'a, T, I> {
+    pub fn new(part: &'static str) -> Self {
+        Alternates {
+            part,
+            implementations: Vec::new(),
+        }
+    }
+
+    pub fn register(mut self, name: &'static str, implementation: impl Fn(&I) -> T + 'a) -> Self {
+        self.implementations.push((name, Box::new(implementation)));
+        self
+    }
+
+    /// Runs every implementation, catching panics so one failing
+    /// implementation shows up as a result instead of ending the run. The
+    /// panic hook is silenced meanwhile, as the message ends up in the result
+    /// and shrinking would print it again for every candidate.
+    fn results(&self, input: &I) -> Vec<(&'static str, Outcome<T>)> {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+
+        let results = self
+            .implementations
+            .iter()
+            .map(|(name, implementation)| {
+                let result = panic::catch_unwind(AssertUnwindSafe(|| implementation(input)));
+                (*name, result.map_err(panic_message))
+            })
+            .collect();
+
+        panic::set_hook(hook);
+
+        results
+    }
+
+    /// Whether the implementations disagree on `input`. A panic counts as
+    /// disagreeing.
+    fn diverges(&self, input: &I) -> bool {
+        !agree(&self.results(input))
+    }
+
+    /// Drops chunks of parts, halving the chunk size whenever nothing can be
+    /// dropped anymore, as long as the implementations keep disagreeing.
+    fn shrink(&self, input: &I) -> I::Owned {
+        let mut current = input.to_owned();
+        let mut chunk = input.parts().div_ceil(2);
+
+        while chunk > 0 {
+            let mut start = 0;
+
+            while start < current.borrow().parts() {
+                let end = (start + chunk).min(current.borrow().parts());
+
+                match current.borrow().without(start..end) {
+                    Some(candidate) if self.diverges(candidate.borrow()) => current = candidate,
+                    _ => start = end,
+                }
+            }
+
+            chunk /= 2;
+        }
+
+        current
+    }
+
+    /// Runs every implementation on `input` and returns the common result.
+    pub fn run(&self, input: &I) -> Result<T, Divergence<T>> {
+        let mut results = self.results(input);
+
+        if agree(&results) {
+            if let (_, Ok(result)) = results.swap_remove(0) {
+                return Ok(result);
+            }
+        }
+
+        let input = self.shrink(input);
+        let results = self.results(input.borrow());
+
+        Err(Divergence {
+            part: self.part,
+            input: input.borrow().to_string(),
+            results,
+        })
+    }
+
+    /// Like [`Alternates::run`], but panics with the divergence.
+    pub fn check(&self, input: &I) -> T {
+        self.run(input)
+            .unwrap_or_else(|divergence| panic!("{divergence}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alternates::Alternates;
+
+    #[test]
+    fn shrink_test() {
+        let alternates = Alternates::new("Sum")
+            .register("sum", |input: &str| {
+                input
+                    .lines()
+                    .map(|l| l.parse::<i64>().unwrap())
+                    .sum::<i64>()
+            })
+            .register("sum_without_sevens", |input: &str| {
+                input
+                    .lines()
+                    .map(|l| l.parse::<i64>().unwrap())
+                    .filter(|n| *n != 7)
+                    .sum::<i64>()
+            });
+
+        assert_eq!(alternates.check("1\n2\n3"), 6);
+
+        let divergence = alternates.run("1\n2\n7\n4\n5\n6").unwrap_err();
+        assert_eq!(divergence.input, "7");
+        assert_eq!(
+            divergence.results,
+            vec![("sum", Ok(7)), ("sum_without_sevens", Ok(0))]
+        );
+    }
+
+    #[test]
+    fn panic_test() {
+        let alternates = Alternates::new("Sum")
+            .register("sum", |input: &str| input.lines().count())
+            .register("no_sevens", |input: &str| {
+                assert!(!input.lines().any(|l| l == "7"), "seven");
+                input.lines().count()
+            });
+
+        assert_eq!(alternates.check("1\n2"), 2);
+
+        let divergence = alternates.run("1\n2\n7\n4").unwrap_err();
+        assert_eq!(divergence.input, "7");
+        assert_eq!(
+            divergence.results,
+            vec![("sum", Ok(1)), ("no_sevens", Err("seven".to_string()))]
+        );
+        assert!(divergence
+            .to_string()
+            .contains("no_sevens:\tpanicked: seven"));
+    }
+}
@@ -0,0 +1,261 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidCard(String),
+    InvalidRank(String),
+    MissingBid(String),
+    CardCount(String),
+    InvalidBid(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidCard(card) => write!(f, "invalid card {card}"),
+            ParseError::InvalidRank(rank) => write!(f, "invalid rank {rank}"),
+            ParseError::MissingBid(line) => write!(f, "missing bid in {line}"),
+            ParseError::CardCount(line) => write!(f, "expected 5 cards in {line}"),
+            ParseError::InvalidBid(bid) => write!(f, "invalid bid {bid}"),
+        }
+    }
+}
+
+/// Defines `Card` with its variants ordered as listed, which is all the
+/// parts differ in, together with parsing and printing by the given symbols.
+#[macro_export]
+macro_rules! cards {
+    ($($card:ident = $symbol:literal),* $(,)?) => {
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+        #[allow(clippy::upper_case_acronyms)]
+        enum Card {
+            $($card),*
+        }
+
+        impl Card {
+            #[allow(dead_code)]
+            const ALL: [Card; 13] = [$(Card::$card),*];
+        }
+
+        impl TryFrom<char> for Card {
+            type Error = $crate::camel::ParseError;
+
+            fn try_from(c: char) -> Result<Self, Self::Error> {
+                match c {
+                    $($symbol => Ok(Card::$card),)*
+                    _ => Err($crate::camel::ParseError::InvalidCard(c.to_string())),
+                }
+            }
+        }
+
+        impl std::str::FromStr for Card {
+            type Err = $crate::camel::ParseError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Card::try_from(c),
+                    _ => Err($crate::camel::ParseError::InvalidCard(s.to_string())),
+                }
+            }
+        }
+
+        impl std::fmt::Display for Card {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let c = match self {
+                    $(Card::$card => $symbol,)*
+                };
+                write!(f, "{c}")
+            }
+        }
+    };
+}
+
+/// The cards of one part, which rank hands by that part's rules.
+pub trait Ranking: Copy + Ord + fmt::Display + TryFrom<char, Error = ParseError> {
+    fn rank(cards: &[Self]) -> Rank;
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
+pub enum Rank {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfKind,
+    FullHouse,
+    FourOfKind,
+    FiveOfKind,
+}
+
+impl Rank {
+    pub const NAMES: [(Rank, &'static str); 7] = [
+        (Rank::HighCard, "High card"),
+        (Rank::OnePair, "One pair"),
+        (Rank::TwoPair, "Two pair"),
+        (Rank::ThreeOfKind, "Three of a kind"),
+        (Rank::FullHouse, "Full house"),
+        (Rank::FourOfKind, "Four of a kind"),
+        (Rank::FiveOfKind, "Five of a kind"),
+    ];
+}
+
+impl FromStr for Rank {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Rank::NAMES
+            .iter()
+            .find(|(_, name)| *name == s)
+            .map(|(rank, _)| *rank)
+            .ok_or_else(|| ParseError::InvalidRank(s.to_string()))
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, name) = Rank::NAMES.iter().find(|(rank, _)| rank == self).unwrap();
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug)]
+pub struct Hand<C> {
+    pub cards: Vec<C>,
+    pub bid: i64,
+    pub rank: Rank,
+}
+
+impl<C: Ord> Eq for Hand<C> {}
+
+impl<C: Ord> Ord for Hand<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let cmp = self.rank.cmp(&other.rank);
+        if cmp != Ordering::Equal {
+            return cmp;
+        }
+
+        for (i, _) in self.cards.iter().enumerate() {
+            let card_cmp = self.cards[i].cmp(&other.cards[i]);
+
+            if card_cmp == Ordering::Equal {
+                continue;
+            }
+
+            return card_cmp;
+        }
+        panic!("Cards are equal")
+    }
+}
+
+impl<C: Ord> PartialOrd for Hand<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: Ord> PartialEq for Hand<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank == other.rank
+    }
+}
+
+/// Parses a line of the puzzle like `32T3K 765`, ranked by the rules of the
+/// part `C` belongs to.
+impl<C: Ranking> FromStr for Hand<C> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (cards, bid) = s
+            .split_once(' ')
+            .ok_or_else(|| ParseError::MissingBid(s.to_string()))?;
+
+        let cards = cards
+            .chars()
+            .map(C::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        if cards.len() != 5 {
+            return Err(ParseError::CardCount(s.to_string()));
+        }
+
+        let bid = bid
+            .parse()
+            .map_err(|_| ParseError::InvalidBid(bid.to_string()))?;
+
+        let rank = C::rank(&cards);
+
+        Ok(Hand { cards, bid, rank })
+    }
+}
+
+impl<C: fmt::Display> fmt::Display for Hand<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for card in &self.cards {
+            write!(f, "{card}")?;
+        }
+        write!(f, " {}", self.bid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::camel::{Hand, ParseError, Rank, Ranking};
+
+    crate::cards! {
+        TWO = '2', THREE = '3', FOUR = '4', FIVE = '5', SIX = '6', SEVEN = '7',
+        EIGHT = '8', NINE = '9', T = 'T', J = 'J', Q = 'Q', K = 'K', A = 'A',
+    }
+
+    impl Ranking for Card {
+        fn rank(_: &[Self]) -> Rank {
+            Rank::HighCard
+        }
+    }
+
+    #[test]
+    fn roundtrip_test() {
+        let hand = "32T3K 765".parse::<Hand<Card>>().unwrap();
+        assert_eq!(
+            hand.cards,
+            vec![Card::THREE, Card::TWO, Card::T, Card::THREE, Card::K]
+        );
+        assert_eq!(hand.bid, 765);
+        assert_eq!(hand.to_string(), "32T3K 765");
+
+        for (rank, name) in Rank::NAMES {
+            assert_eq!(rank.to_string(), name);
+            assert_eq!(name.parse::<Rank>(), Ok(rank));
+        }
+        for card in Card::ALL {
+            assert_eq!(card.to_string().parse::<Card>(), Ok(card));
+        }
+        assert!(Card::ALL.is_sorted());
+    }
+
+    #[test]
+    fn parse_error_test() {
+        assert_eq!(
+            "32T3X 765".parse::<Hand<Card>>().unwrap_err(),
+            ParseError::InvalidCard("X".to_string())
+        );
+        assert_eq!(
+            "32T3K".parse::<Hand<Card>>().unwrap_err(),
+            ParseError::MissingBid("32T3K".to_string())
+        );
+        assert_eq!(
+            "32T3 765".parse::<Hand<Card>>().unwrap_err(),
+            ParseError::CardCount("32T3 765".to_string())
+        );
+        assert_eq!(
+            "32T3K bid".parse::<Hand<Card>>().unwrap_err(),
+            ParseError::InvalidBid("bid".to_string())
+        );
+        assert_eq!(
+            "Two of a kind".parse::<Rank>(),
+            Err(ParseError::InvalidRank("Two of a kind".to_string()))
+        );
+        assert_eq!(
+            "10".parse::<Card>(),
+            Err(ParseError::InvalidCard("10".to_string()))
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use std::{fmt, io, io::BufRead, str::FromStr};
+
+/// What to do with a line that contains no digit at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingPolicy {
+    Skip,
+    Zero,
+    Error,
+}
+
+impl FromStr for MissingPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(MissingPolicy::Skip),
+            "zero" => Ok(MissingPolicy::Zero),
+            "error" => Ok(MissingPolicy::Error),
+            val => Err(format!(
+                "invalid policy {val}, expected skip, zero or error"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct MissingDigits {
+    pub line: usize,
+}
+
+impl fmt::Display for MissingDigits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {} has no digits", self.line)
+    }
+}
+
+#[derive(Debug)]
+pub enum CalibrationError {
+    Io(io::Error),
+    MissingDigits(MissingDigits),
+}
+
+impl From<io::Error> for CalibrationError {
+    fn from(e: io::Error) -> Self {
+        CalibrationError::Io(e)
+    }
+}
+
+impl From<MissingDigits> for CalibrationError {
+    fn from(e: MissingDigits) -> Self {
+        CalibrationError::MissingDigits(e)
+    }
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationError::Io(e) => write!(f, "{e}"),
+            CalibrationError::MissingDigits(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// Sum of the calibration values, collected line by line.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Calibration {
+    pub sum: u64,
+    /// Lines without digits which were skipped or counted as zero
+    pub missing: usize,
+    policy: MissingPolicy,
+}
+
+impl Calibration {
+    pub fn new(policy: MissingPolicy) -> Self {
+        Calibration {
+            sum: 0,
+            missing: 0,
+            policy,
+        }
+    }
+
+    /// Adds the value of the 1-based `line`, `None` if it has no digits.
+    pub fn add(&mut self, line: usize, value: Option<u32>) -> Result<(), MissingDigits> {
+        match (value, self.policy) {
+            (Some(value), _) => self.sum += value as u64,
+            (None, MissingPolicy::Error) => return Err(MissingDigits { line }),
+            (None, MissingPolicy::Skip | MissingPolicy::Zero) => self.missing += 1,
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Calibration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.sum)?;
+
+        match (self.missing, self.policy) {
+            (0, _) => Ok(()),
+            (n, MissingPolicy::Zero) => write!(f, " ({n} lines without digits counted as zero)"),
+            (n, _) => write!(f, " ({n} lines without digits skipped)"),
+        }
+    }
+}
+
+/// Calls `f` with every line of `reader` and its 1-based number while
+/// reusing a single line buffer, so memory stays constant however large the
+/// input is. Stops at the first error `f` returns.
+pub fn for_each_line<R: BufRead, E: From<io::Error>>(
+    mut reader: R,
+    mut f: impl FnMut(usize, &str) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut line = String::new();
+    let mut number = 0;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        number += 1;
+
+        f(number, line.trim_end_matches(['\n', '\r']))?;
+    }
+}
+
+/// Feeds every line of `reader` with its 1-based number through `value`.
+pub fn calibrate<R: BufRead>(
+    reader: R,
+    policy: MissingPolicy,
+    mut value: impl FnMut(usize, &str) -> Option<u32>,
+) -> Result<Calibration, CalibrationError> {
+    let mut calibration = Calibration::new(policy);
+
+    for_each_line(reader, |number, line| {
+        calibration
+            .add(number, value(number, line))
+            .map_err(CalibrationError::from)
+    })?;
+
+    Ok(calibration)
+}
@@ -1,156 +1,579 @@
-use once_cell::sync::Lazy;
-use regex::Regex;
-use std::fs;
+mod calibration;
+mod matcher;
+mod vocabulary;
+
+use aoc::{
+    alternates::Alternates,
+    trace::{Token, Trace, Verbosity},
+};
+use calibration::{
+    calibrate, for_each_line, Calibration, CalibrationError, MissingDigits, MissingPolicy,
+};
+use matcher::DigitMatcher;
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufRead, BufReader},
+    process,
+};
+use vocabulary::Vocabulary;
+
+fn token(line: &str, start: usize, end: usize) -> Token {
+    Token {
+        position: start,
+        text: line[start..end].to_string(),
+    }
+}
 
-fn get_numbers(line: &str) -> Vec<u32> {
-    let mut v: Vec<u32> = Vec::new();
+fn part_one(
+    input: impl BufRead,
+    policy: MissingPolicy,
+    trace: &mut Trace,
+) -> Result<Calibration, CalibrationError> {
+    calibrate(input, policy, |number, line| {
+        let first = line.bytes().position(|b| b.is_ascii_digit());
+        let last = line.bytes().rposition(|b| b.is_ascii_digit());
+
+        let found = first.zip(last);
+        let value = found.map(|(first, last)| {
+            let digit = |i: usize| (line.as_bytes()[i] - b'0') as u32;
+            digit(first) * 10 + digit(last)
+        });
+
+        if trace.enabled() {
+            let tokens = found
+                .map(|(first, last)| {
+                    vec![token(line, first, first + 1), token(line, last, last + 1)]
+                })
+                .unwrap_or_default();
+            trace.record("PartOne", number, line, tokens, value);
+        }
+
+        value
+    })
+}
 
-    for c in line.chars() {
-        if c.is_ascii_digit() {
-            v.push(c.to_digit(10).unwrap());
+/// The value of a line by the first and last digit the [`DigitMatcher`]
+/// finds, recorded as `PartTwo`.
+fn part_two_values(vocabulary: &Vocabulary) -> impl Fn(usize, &str, &mut Trace) -> Option<u32> {
+    let matcher = DigitMatcher::new(vocabulary);
+
+    move |number, line, trace| {
+        let found = matcher.first_last_digits(line);
+        let value = found.map(|(first, last)| first.value * 10 + last.value);
+
+        if trace.enabled() {
+            let tokens = found
+                .map(|(first, last)| {
+                    vec![
+                        token(line, first.start, first.end),
+                        token(line, last.start, last.end),
+                    ]
+                })
+                .unwrap_or_default();
+            trace.record("PartTwo", number, line, tokens, value);
         }
+
+        value
     }
+}
 
-    return v;
+fn part_two(
+    input: impl BufRead,
+    vocabulary: &Vocabulary,
+    policy: MissingPolicy,
+    trace: &mut Trace,
+) -> Result<Calibration, CalibrationError> {
+    let values = part_two_values(vocabulary);
+
+    calibrate(input, policy, |number, line| values(number, line, trace))
 }
 
-fn part_one(lines: &Vec<&str>) -> u32 {
-    let mut sum: u32 = 0;
+/// The value of a line by searching the words forwards and, reversed, in the
+/// reversed line, recorded as `Ruben`.
+fn ruben_values(vocabulary: &Vocabulary) -> impl Fn(usize, &str, &mut Trace) -> Option<u32> + '_ {
+    let re = vocabulary.regex();
+
+    let reversed = vocabulary.reversed();
+    let re2 = reversed.regex();
 
-    for line in lines {
-        let numbers = get_numbers(line);
+    move |number, line, trace| {
+        let first = re.find(line);
+        let x2 = first.and_then(|x| vocabulary.value(x.as_str()));
 
-        sum += numbers.first().unwrap() * 10 + numbers.last().unwrap();
+        let x = line.chars().rev().collect::<String>();
+
+        let last = re2.find(x.as_str());
+        let y = last.and_then(|x| reversed.value(x.as_str()));
+
+        let sol = x2.zip(y).map(|(x2, y)| x2 * 10 + y);
+
+        if trace.enabled() {
+            let mut tokens = Vec::new();
+            if let Some(first) = first {
+                tokens.push(token(line, first.start(), first.end()));
+            }
+            // reversing by chars mirrors the byte offsets
+            if let Some(last) = last {
+                tokens.push(token(
+                    line,
+                    line.len() - last.end(),
+                    line.len() - last.start(),
+                ));
+            }
+            trace.record("Ruben", number, line, tokens, sol);
+        }
+
+        sol
     }
+}
 
-    sum
+fn get_sum_regex_solution02(
+    input: impl BufRead,
+    vocabulary: &Vocabulary,
+    policy: MissingPolicy,
+    trace: &mut Trace,
+) -> Result<Calibration, CalibrationError> {
+    let values = ruben_values(vocabulary);
+
+    calibrate(input, policy, |number, line| values(number, line, trace))
 }
 
-fn str_to_int(s: &str) -> u32 {
-    return match s {
-        "one" => 1,
-        "two" => 2,
-        "three" => 3,
-        "four" => 4,
-        "five" => 5,
-        "six" => 6,
-        "seven" => 7,
-        "eight" => 8,
-        "nine" => 9,
-        _ => 0,
-    };
+/// Runs [`part_two`] and [`get_sum_regex_solution02`] side by side in a
+/// single pass over `input`. As both record every line right after another,
+/// a [`Trace::comparing`] them can drop each line they agree on at once.
+fn part_two_lockstep(
+    input: impl BufRead,
+    vocabulary: &Vocabulary,
+    policy: MissingPolicy,
+    trace: &mut Trace,
+) -> io::Result<[Result<Calibration, MissingDigits>; 2]> {
+    let part_two = part_two_values(vocabulary);
+    let ruben = ruben_values(vocabulary);
+    let mut results = [Ok(Calibration::new(policy)), Ok(Calibration::new(policy))];
+
+    for_each_line(input, |number, line| {
+        let values = [part_two(number, line, trace), ruben(number, line, trace)];
+
+        for (result, value) in results.iter_mut().zip(values) {
+            let added = match result {
+                Ok(calibration) => calibration.add(number, value),
+                Err(_) => Ok(()),
+            };
+            if let Err(e) = added {
+                *result = Err(e);
+            }
+        }
+
+        Ok::<_, io::Error>(())
+    })?;
+
+    Ok(results)
 }
 
-fn get_numbers_part_two(line: &str) -> Vec<u32> {
-    let chars = [
-        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-    ];
-    let mut v: Vec<u32> = Vec::new();
+fn part_two_alternates(
+    vocabulary: &Vocabulary,
+    policy: MissingPolicy,
+) -> Alternates<'_, Result<u64, String>> {
+    let run = |result: Result<Calibration, CalibrationError>| {
+        result.map(|c| c.sum).map_err(|e| e.to_string())
+    };
 
-    for (i, c) in line.chars().enumerate() {
-        if c.is_ascii_digit() {
-            v.push(c.to_digit(10).unwrap());
-            continue;
+    Alternates::new("PartTwo")
+        .register("part_two", move |input: &str| {
+            run(part_two(
+                input.as_bytes(),
+                vocabulary,
+                policy,
+                &mut Trace::default(),
+            ))
+        })
+        .register("get_sum_regex_solution02", move |input: &str| {
+            run(get_sum_regex_solution02(
+                input.as_bytes(),
+                vocabulary,
+                policy,
+                &mut Trace::default(),
+            ))
+        })
+}
+
+fn main() {
+    let mut file_path = "input/day01.txt".to_string();
+    let mut vocabulary = Vocabulary::english();
+    let mut policy = MissingPolicy::Error;
+    let mut verbosity = Verbosity::Quiet;
+    let mut check = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--vocabulary" => {
+                let name = args.next().unwrap_or_default();
+                vocabulary = Vocabulary::load(&name).unwrap_or_else(|e| {
+                    eprintln!("Vocabulary {e}");
+                    process::exit(1);
+                });
+            }
+            "--missing" => {
+                let name = args.next().unwrap_or_default();
+                policy = name.parse().unwrap_or_else(|e| {
+                    eprintln!("Missing {e}");
+                    process::exit(1);
+                });
+            }
+            "--check" => check = true,
+            "-v" | "-vv" => verbosity = Verbosity::from_flag(&arg).unwrap(),
+            _ => file_path = arg,
         }
+    }
+
+    println!("Reading {}", file_path);
 
-        for num in chars {
-            if i + num.len() > line.len() {
-                continue;
+    if check {
+        let contents = match fs::read_to_string(&file_path) {
+            Ok(file) => file,
+            Err(e) => panic!("Read file: {}", e),
+        };
+
+        match part_two_alternates(&vocabulary, policy).run(&contents) {
+            Ok(Ok(sum)) => println!("PartTwo:\t{sum}, all implementations agree"),
+            Ok(Err(e)) => {
+                eprintln!("PartTwo:\t{e}");
+                process::exit(1);
+            }
+            Err(divergence) => {
+                eprint!("{divergence}");
+                process::exit(1);
             }
+        }
+        return;
+    }
 
-            let s = &line[i..i + num.len()];
+    let open = || match File::open(&file_path) {
+        Ok(file) => BufReader::new(file),
+        Err(e) => panic!("Read file: {}", e),
+    };
+
+    // part one solves a different puzzle, so it is not compared with the others
+    let mut trace_one = Trace::new(verbosity);
+    let mut trace_two = Trace::new(verbosity).comparing(&["PartTwo", "Ruben"]);
 
-            if s == num {
-                let x = str_to_int(s);
-                v.push(x);
-                break;
+    let [two, ruben] = match part_two_lockstep(open(), &vocabulary, policy, &mut trace_two) {
+        Ok(results) => results.map(|result| result.map_err(CalibrationError::from)),
+        Err(e) => panic!("Read file: {}", e),
+    };
+
+    let results = [
+        ("PartOne:\t", part_one(open(), policy, &mut trace_one)),
+        ("PartTwo:\t", two),
+        ("Ruben:\t\t", ruben),
+    ];
+
+    let mut failed = false;
+    for (name, result) in results {
+        match result {
+            Ok(calibration) => println!("{name}{calibration}"),
+            Err(e) => {
+                eprintln!("{name}{e}");
+                failed = true;
             }
         }
     }
 
-    return v;
+    if verbosity == Verbosity::All {
+        eprint!("{}", trace_one.render());
+    }
+    if trace_two.disagreements() > 0 {
+        eprintln!("Disagreements:\t{}", trace_two.disagreements());
+    }
+    eprint!("{}", trace_two.render());
+
+    if failed {
+        process::exit(1);
+    }
 }
 
-fn part_two(lines: &Vec<&str>) -> u32 {
-    let mut sum: u32 = 0;
+#[cfg(test)]
+mod tests {
+    use crate::{
+        calibration::MissingDigits, get_sum_regex_solution02, part_one, part_two,
+        part_two_alternates, part_two_lockstep, CalibrationError, DigitMatcher, MissingPolicy,
+        Trace, Verbosity, Vocabulary,
+    };
 
-    for line in lines {
-        let parts = get_numbers_part_two(line);
+    fn first_last(matcher: &DigitMatcher, line: &str) -> Option<(u32, u32)> {
+        matcher
+            .first_last_digits(line)
+            .map(|(first, last)| (first.value, last.value))
+    }
 
-        sum += parts.first().unwrap() * 10 + parts.last().unwrap();
+    #[test]
+    fn part_one_test() {
+        let input = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet\n";
+
+        assert_eq!(
+            part_one(
+                input.as_bytes(),
+                MissingPolicy::Error,
+                &mut Trace::default()
+            )
+            .unwrap()
+            .sum,
+            142
+        );
     }
 
-    sum
-}
+    #[test]
+    fn part_two_test() {
+        let input = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+        assert_eq!(
+            part_two(
+                input.as_bytes(),
+                &Vocabulary::english(),
+                MissingPolicy::Error,
+                &mut Trace::default()
+            )
+            .unwrap()
+            .sum,
+            281
+        );
+        assert_eq!(
+            part_two_alternates(&Vocabulary::english(), MissingPolicy::Error).check(input),
+            Ok(281)
+        );
+    }
 
-fn get_sum_regex_solution02(input: String) -> u32 {
-    static RE: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"(one|two|three|four|five|six|seven|eight|nine|\d)").unwrap());
-
-    static RE2: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"(eno|owt|eerht|ruof|evif|xis|neves|thgie|enin|\d)").unwrap());
-
-    input
-        .lines()
-        .map(|x| {
-            // print!("{}:           ", x);
-            let mut iter = RE.captures_iter(&x).map(|x| match &x[0] {
-                "one" => 1,
-                "two" => 2,
-                "three" => 3,
-                "four" => 4,
-                "five" => 5,
-                "six" => 6,
-                "seven" => 7,
-                "eight" => 8,
-                "nine" => 9,
-                a => a.parse().unwrap(),
-            });
-            let x2 = iter.next().unwrap();
-            // if no further value there, use the first
-
-            let x = x.chars().rev().collect::<String>();
-
-            let mut iter2 = RE2.captures_iter(x.as_str()).map(|x| match &x[0] {
-                "eno" => 1,
-                "owt" => 2,
-                "eerht" => 3,
-                "ruof" => 4,
-                "evif" => 5,
-                "xis" => 6,
-                "neves" => 7,
-                "thgie" => 8,
-                "enin" => 9,
-                a => a.parse().unwrap(),
-            });
-
-            let y = iter2.next().unwrap();
-
-            let sol = x2 * 10 + y;
-
-            println!("{}:\tSol {}", x, sol);
-            sol
-        })
-        .sum()
-}
+    #[test]
+    fn overlapping_test() {
+        let matcher = DigitMatcher::new(&Vocabulary::english());
+
+        assert_eq!(first_last(&matcher, "eightwo"), Some((8, 2)));
+        assert_eq!(first_last(&matcher, "oneight"), Some((1, 8)));
+        assert_eq!(first_last(&matcher, "twoneighthreeightwo"), Some((2, 2)));
+        assert_eq!(first_last(&matcher, "zoneight234"), Some((1, 4)));
+        assert_eq!(first_last(&matcher, "3oneight"), Some((3, 8)));
+        assert_eq!(first_last(&matcher, "seven"), Some((7, 7)));
+        assert_eq!(first_last(&matcher, "abc"), None);
+    }
 
-fn main() {
-    let file_path = "input/day01.txt";
+    #[test]
+    fn multibyte_test() {
+        let matcher = DigitMatcher::new(&Vocabulary::english());
+
+        assert_eq!(first_last(&matcher, "äone"), Some((1, 1)));
+        assert_eq!(first_last(&matcher, "ö2ünine€"), Some((2, 9)));
+        assert_eq!(first_last(&matcher, "一two二three三"), Some((2, 3)));
+        assert_eq!(first_last(&matcher, "t🎄wo🎄eightwo"), Some((8, 2)));
+        assert_eq!(first_last(&matcher, "ñineñ"), None);
+        assert_eq!(
+            part_two(
+                "éeight7ß\r\nsevenü\r\n".as_bytes(),
+                &Vocabulary::english(),
+                MissingPolicy::Error,
+                &mut Trace::default()
+            )
+            .unwrap()
+            .sum,
+            87 + 77
+        );
+
+        // only ASCII digits count, in whichever way the line is searched
+        let input = "one٣x\n٣7٣";
+        let english = Vocabulary::english();
+        for sum in [
+            part_two(
+                input.as_bytes(),
+                &english,
+                MissingPolicy::Error,
+                &mut Trace::default(),
+            ),
+            get_sum_regex_solution02(
+                input.as_bytes(),
+                &english,
+                MissingPolicy::Error,
+                &mut Trace::default(),
+            ),
+        ] {
+            assert_eq!(sum.unwrap().sum, 11 + 77);
+        }
+        assert_eq!(
+            part_two_alternates(&english, MissingPolicy::Error).check(input),
+            Ok(11 + 77)
+        );
+    }
 
-    println!("Reading {}", file_path);
+    #[test]
+    fn german_test() {
+        let german = Vocabulary::german();
+        let input = "zweins3\nfünfsiebenacht\n9neunzehn\nxachtzwei1";
+
+        assert_eq!(
+            part_two(
+                input.as_bytes(),
+                &german,
+                MissingPolicy::Error,
+                &mut Trace::default()
+            )
+            .unwrap()
+            .sum,
+            23 + 58 + 99 + 81
+        );
+        assert_eq!(
+            get_sum_regex_solution02(
+                input.as_bytes(),
+                &german,
+                MissingPolicy::Error,
+                &mut Trace::default()
+            )
+            .unwrap()
+            .sum,
+            23 + 58 + 99 + 81
+        );
+        assert_eq!(
+            part_two_alternates(&german, MissingPolicy::Error).check(input),
+            Ok(23 + 58 + 99 + 81)
+        );
+    }
 
-    let contents = match fs::read_to_string(file_path) {
-        Ok(file) => file,
-        Err(e) => panic!("Read file: {}", e),
-    };
+    #[test]
+    fn custom_vocabulary_test() {
+        let vocabulary = "# prefixes of each other\nsix 6\nsixty 7\n\nten 1"
+            .parse::<Vocabulary>()
+            .unwrap();
+        let matcher = DigitMatcher::new(&vocabulary);
+
+        assert_eq!(first_last(&matcher, "sixty"), Some((7, 7)));
+        assert_eq!(first_last(&matcher, "tensix"), Some((1, 6)));
+        assert_eq!(first_last(&matcher, "sixty six"), Some((7, 6)));
+        assert_eq!(vocabulary.reversed().value("ytxis"), Some(7));
+        assert_eq!(
+            get_sum_regex_solution02(
+                "sixty".as_bytes(),
+                &vocabulary,
+                MissingPolicy::Error,
+                &mut Trace::default()
+            )
+            .unwrap()
+            .sum,
+            77
+        );
+        assert!("one".parse::<Vocabulary>().is_err());
+        assert!("ten 10".parse::<Vocabulary>().is_err());
+        assert!("".parse::<Vocabulary>().is_err());
+    }
 
-    let lines = contents.lines().collect();
+    #[test]
+    fn missing_digits_test() {
+        let input = "1abc2\n\npqr3stu8vwx\nnothing here\n";
+        let english = Vocabulary::english();
+
+        let skipped =
+            part_one(input.as_bytes(), MissingPolicy::Skip, &mut Trace::default()).unwrap();
+        assert_eq!((skipped.sum, skipped.missing), (12 + 38, 2));
+
+        let zero = part_two(
+            input.as_bytes(),
+            &english,
+            MissingPolicy::Zero,
+            &mut Trace::default(),
+        )
+        .unwrap();
+        assert_eq!((zero.sum, zero.missing), (12 + 38, 2));
+
+        assert!(matches!(
+            part_one(
+                input.as_bytes(),
+                MissingPolicy::Error,
+                &mut Trace::default()
+            ),
+            Err(CalibrationError::MissingDigits(MissingDigits { line: 2 }))
+        ));
+        assert!(matches!(
+            part_two(
+                &input.as_bytes()[7..],
+                &english,
+                MissingPolicy::Error,
+                &mut Trace::default()
+            ),
+            Err(CalibrationError::MissingDigits(MissingDigits { line: 2 }))
+        ));
+        assert!(matches!(
+            get_sum_regex_solution02(
+                input.as_bytes(),
+                &english,
+                MissingPolicy::Error,
+                &mut Trace::default()
+            ),
+            Err(CalibrationError::MissingDigits(MissingDigits { line: 2 }))
+        ));
+    }
 
-    let sum_one = part_one(&lines);
-    let sum_two = part_two(&lines);
-    let ruben = get_sum_regex_solution02(contents);
+    #[test]
+    fn large_input_test() {
+        let lines = (0..100_000u32).map(|i| format!("x{}yz{}eightwo\n", i % 10, i % 7));
+        let input = std::io::BufReader::new(std::io::Cursor::new(lines.collect::<String>()));
+
+        let calibration = part_two(
+            input,
+            &Vocabulary::english(),
+            MissingPolicy::Error,
+            &mut Trace::default(),
+        )
+        .unwrap();
+
+        let expected = (0..100_000u64).map(|i| (i % 10) * 10 + 2).sum::<u64>();
+        assert_eq!(calibration.sum, expected);
+    }
 
-    println!("PartOne:\t{sum_one}");
-    println!("PartTwo:\t{sum_two}");
-    println!("Ruben:\t\t{ruben}");
+    #[test]
+    fn trace_test() {
+        let input = "two1nine\nzoneight\n";
+        let english = Vocabulary::english();
+        let mut trace = Trace::new(Verbosity::Disagreements);
+
+        part_two(input.as_bytes(), &english, MissingPolicy::Error, &mut trace).unwrap();
+        get_sum_regex_solution02(input.as_bytes(), &english, MissingPolicy::Error, &mut trace)
+            .unwrap();
+        assert_eq!(trace.disagreements(), 0);
+        assert_eq!(trace.render(), "");
+
+        // run side by side, agreeing lines are not kept at all
+        let mut trace = Trace::new(Verbosity::Disagreements).comparing(&["PartTwo", "Ruben"]);
+        let [two, ruben] = part_two_lockstep(
+            "two1nine\nabc\nzoneight\n".as_bytes(),
+            &english,
+            MissingPolicy::Error,
+            &mut trace,
+        )
+        .unwrap();
+        assert_eq!(two, Err(MissingDigits { line: 2 }));
+        assert_eq!(ruben, Err(MissingDigits { line: 2 }));
+        assert_eq!(trace.lines(), 0);
+
+        trace.record("PartTwo", 4, "x", Vec::new(), Some(1));
+        trace.record("Ruben", 4, "x", Vec::new(), Some(2));
+        assert_eq!(trace.lines(), 1);
+        assert_eq!(trace.disagreements(), 1);
+
+        let mut trace = Trace::new(Verbosity::All);
+        part_two(input.as_bytes(), &english, MissingPolicy::Error, &mut trace).unwrap();
+        get_sum_regex_solution02(input.as_bytes(), &english, MissingPolicy::Error, &mut trace)
+            .unwrap();
+
+        assert_eq!(
+            trace.render(),
+            "Line | Input    | PartTwo            | Ruben
+1    | two1nine | two@0 nine@4 = 29  | two@0 nine@4 = 29
+2    | zoneight | one@1 eight@3 = 18 | one@1 eight@3 = 18
+"
+        );
+    }
 }
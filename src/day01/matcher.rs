@@ -0,0 +1,66 @@
+use aho_corasick::{AhoCorasick, Anchored, Input, MatchKind, StartKind};
+
+use crate::Vocabulary;
+
+/// A digit or word found at the bytes `start..end` of a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Digit {
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+}
+
+/// Finds digits and the words of a vocabulary with a single automaton. Every
+/// position is tried on its own, so overlapping words like the `one` and
+/// `eight` in `oneight` are both found.
+pub struct DigitMatcher {
+    automaton: AhoCorasick,
+    values: Vec<u32>,
+}
+
+impl DigitMatcher {
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        let mut patterns = Vec::new();
+        let mut values = Vec::new();
+
+        for digit in 0..=9 {
+            patterns.push(digit.to_string());
+            values.push(digit);
+        }
+
+        for (word, value) in vocabulary.words() {
+            patterns.push(word.to_string());
+            values.push(value);
+        }
+
+        DigitMatcher {
+            automaton: AhoCorasick::builder()
+                .match_kind(MatchKind::LeftmostLongest)
+                .start_kind(StartKind::Anchored)
+                .build(patterns)
+                .unwrap(),
+            values,
+        }
+    }
+
+    /// The digit starting exactly at byte `start`, the longest word wins.
+    fn digit_at(&self, line: &str, start: usize) -> Option<Digit> {
+        let input = Input::new(line).range(start..).anchored(Anchored::Yes);
+
+        self.automaton.find(input).map(|m| Digit {
+            start: m.start(),
+            end: m.end(),
+            value: self.values[m.pattern()],
+        })
+    }
+
+    /// The first and the last digit of the line. Scans inwards from both ends
+    /// and stops at the first hit on each side, without allocating or
+    /// reversing the line.
+    pub fn first_last_digits(&self, line: &str) -> Option<(Digit, Digit)> {
+        let first = (0..line.len()).find_map(|i| self.digit_at(line, i))?;
+        let last = (0..line.len()).rev().find_map(|i| self.digit_at(line, i))?;
+
+        Some((first, last))
+    }
+}
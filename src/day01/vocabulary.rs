@@ -0,0 +1,131 @@
+use std::{cmp::Reverse, fmt, fs, str::FromStr};
+
+use regex::Regex;
+
+const ENGLISH: &str = include_str!("vocabulary/english.txt");
+const GERMAN: &str = include_str!("vocabulary/german.txt");
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum VocabularyError {
+    InvalidLine(usize, String),
+    Empty,
+}
+
+impl fmt::Display for VocabularyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VocabularyError::InvalidLine(number, line) => {
+                write!(
+                    f,
+                    "line {number}: expected \"<word> <value>\", got \"{line}\""
+                )
+            }
+            VocabularyError::Empty => write!(f, "vocabulary has no words"),
+        }
+    }
+}
+
+/// The words spelling out digits, loaded from lines of `<word> <value>`.
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+}
+
+impl Vocabulary {
+    pub fn english() -> Self {
+        ENGLISH.parse().unwrap()
+    }
+
+    pub fn german() -> Self {
+        GERMAN.parse().unwrap()
+    }
+
+    /// A built-in vocabulary by name, otherwise one read from the file `name`.
+    pub fn load(name: &str) -> Result<Self, String> {
+        match name {
+            "english" => Ok(Vocabulary::english()),
+            "german" => Ok(Vocabulary::german()),
+            path => fs::read_to_string(path)
+                .map_err(|e| format!("{path}: {e}"))?
+                .parse()
+                .map_err(|e| format!("{path}: {e}")),
+        }
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.words
+            .iter()
+            .map(|(word, value)| (word.as_str(), *value))
+    }
+
+    /// The value of a word or of a single ascii digit.
+    pub fn value(&self, token: &str) -> Option<u32> {
+        if let Some(value) = self.words().find(|(word, _)| *word == token) {
+            return Some(value.1);
+        }
+
+        match token.as_bytes() {
+            [digit] if digit.is_ascii_digit() => Some((digit - b'0') as u32),
+            _ => None,
+        }
+    }
+
+    /// The same vocabulary with every word spelled backwards, to match on
+    /// reversed lines.
+    pub fn reversed(&self) -> Self {
+        let words = self
+            .words
+            .iter()
+            .map(|(word, value)| (word.chars().rev().collect(), *value))
+            .collect();
+
+        Vocabulary { words }
+    }
+
+    /// Matches any word or single ASCII digit, preferring the longest word
+    /// if several start at the same position.
+    pub fn regex(&self) -> Regex {
+        let mut words = self.words().map(|(word, _)| word).collect::<Vec<_>>();
+        words.sort_by_key(|word| Reverse(word.len()));
+
+        let alternatives = words
+            .into_iter()
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join("|");
+
+        Regex::new(&format!(r"({alternatives}|[0-9])")).unwrap()
+    }
+}
+
+impl FromStr for Vocabulary {
+    type Err = VocabularyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = Vec::new();
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || VocabularyError::InvalidLine(i + 1, line.to_string());
+            let (word, value) = line.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            let value = value.trim().parse::<u32>().map_err(|_| invalid())?;
+
+            if value > 9 {
+                return Err(invalid());
+            }
+
+            words.push((word.to_string(), value));
+        }
+
+        if words.is_empty() {
+            return Err(VocabularyError::Empty);
+        }
+
+        Ok(Vocabulary { words })
+    }
+}
@@ -0,0 +1,379 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    ops::Range,
+    str::FromStr,
+};
+
+use aoc::alternates::Shrink;
+
+use crate::{lookup, number::Number, MapSingle};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AlmanacError {
+    MissingSeeds,
+    /// The seeds line holds no seed, or in ranges, only empty ones
+    NoSeeds,
+    /// The last seed when the seeds line can't be read as pairs
+    UnpairedSeed(String),
+    InvalidNumber(String),
+    InvalidHeader(String),
+    InvalidLine(String),
+    /// Two blocks converting from the same category
+    DuplicateSource(String),
+    /// Nothing converts from this category before `location` is reached
+    BrokenChain(String),
+    Cycle(String),
+    /// Headers of blocks which are not on the chain from `seed` to `location`
+    Unused(Vec<String>),
+    NoConversion {
+        from: String,
+        to: String,
+    },
+    /// A block converting some values in more than one way
+    OverlappingSources(String),
+    /// A line whose ranges end past the largest number of the type read into
+    Overflow(String),
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::MissingSeeds => write!(f, "no seeds line given"),
+            AlmanacError::NoSeeds => write!(f, "there are no seeds to map"),
+            AlmanacError::UnpairedSeed(seed) => write!(f, "seed {seed} has no range length"),
+            AlmanacError::InvalidNumber(n) => write!(f, "invalid number \"{n}\""),
+            AlmanacError::InvalidHeader(header) => write!(f, "invalid block header \"{header}\""),
+            AlmanacError::InvalidLine(line) => write!(f, "invalid mapping line \"{line}\""),
+            AlmanacError::DuplicateSource(category) => {
+                write!(f, "more than one block converts from {category}")
+            }
+            AlmanacError::BrokenChain(category) => {
+                write!(f, "nothing converts from {category} towards location")
+            }
+            AlmanacError::Cycle(category) => write!(f, "the chain returns to {category}"),
+            AlmanacError::Unused(headers) => {
+                write!(f, "blocks not on the chain: {}", headers.join(", "))
+            }
+            AlmanacError::NoConversion { from, to } => {
+                write!(f, "there is no conversion from {from} to {to}")
+            }
+            AlmanacError::OverlappingSources(block) => {
+                write!(f, "source ranges overlap in {block}")
+            }
+            AlmanacError::Overflow(line) => write!(f, "ranges of \"{line}\" overflow"),
+        }
+    }
+}
+
+/// One `X-to-Y map:` block.
+#[derive(Debug, Clone)]
+pub struct Block<N> {
+    pub from: String,
+    pub to: String,
+    pub ranges: Vec<MapSingle<N>>,
+}
+
+impl<N: Number> FromStr for Block<N> {
+    type Err = AlmanacError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().map(str::trim);
+
+        let header = lines.next().unwrap_or_default();
+        let (from, to) = header
+            .strip_suffix(" map:")
+            .and_then(|names| names.split_once("-to-"))
+            .ok_or_else(|| AlmanacError::InvalidHeader(header.to_string()))?;
+
+        let ranges = lines.map(parse_entry).collect::<Result<_, _>>()?;
+
+        Ok(Block {
+            from: from.to_string(),
+            to: to.to_string(),
+            ranges,
+        })
+    }
+}
+
+impl<N: Number> fmt::Display for Block<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-to-{} map:", self.from, self.to)?;
+        for range in &self.ranges {
+            write!(
+                f,
+                "\n{} {} {}",
+                range.dest.start,
+                range.source.start,
+                range.source.end - range.source.start
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_entry<N: Number>(line: &str) -> Result<MapSingle<N>, AlmanacError> {
+    let numbers = line
+        .split_whitespace()
+        .map(parse_number)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let [dest, source, length] = numbers[..] else {
+        return Err(AlmanacError::InvalidLine(line.to_string()));
+    };
+
+    MapSingle::new(dest, source, length).ok_or_else(|| AlmanacError::Overflow(line.to_string()))
+}
+
+fn parse_number<N: Number>(n: &str) -> Result<N, AlmanacError> {
+    n.parse()
+        .map_err(|_| AlmanacError::InvalidNumber(n.to_string()))
+}
+
+/// The seeds and the blocks, ordered as the chain from `seed` to `location`.
+#[derive(Debug, Clone)]
+pub struct Almanac<N> {
+    pub seeds: Vec<N>,
+    pub blocks: Vec<Block<N>>,
+}
+
+impl<N: Number> Almanac<N> {
+    /// The seeds line read as pairs of start and length.
+    pub fn seed_ranges(&self) -> Result<Vec<Range<N>>, AlmanacError> {
+        if self.seeds.len() % 2 == 1 {
+            let last = self.seeds[self.seeds.len() - 1];
+            return Err(AlmanacError::UnpairedSeed(last.to_string()));
+        }
+
+        self.seeds
+            .chunks_exact(2)
+            .map(|pair| match pair[0].checked_add(pair[1]) {
+                Some(end) => Ok(pair[0]..end),
+                None => Err(AlmanacError::Overflow(format!(
+                    "seeds: {} {}",
+                    pair[0], pair[1]
+                ))),
+            })
+            .collect()
+    }
+
+    /// Converts `value` from category `from` along the chain to `to`.
+    pub fn convert(&self, from: &str, to: &str, value: N) -> Result<N, AlmanacError> {
+        let no_conversion = || AlmanacError::NoConversion {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+
+        if from == to && self.blocks.iter().any(|b| b.from == from || b.to == to) {
+            return Ok(value);
+        }
+
+        let start = self
+            .blocks
+            .iter()
+            .position(|block| block.from == from)
+            .ok_or_else(no_conversion)?;
+
+        let mut value = value;
+        for block in &self.blocks[start..] {
+            value = lookup(&block.ranges, value);
+
+            if block.to == to {
+                return Ok(value);
+            }
+        }
+
+        Err(no_conversion())
+    }
+}
+
+/// Written back in the puzzle format.
+impl<N: Number> fmt::Display for Almanac<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seeds:")?;
+        for seed in &self.seeds {
+            write!(f, " {seed}")?;
+        }
+        for block in &self.blocks {
+            write!(f, "\n\n{block}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Cut into seed ranges, the blocks are kept as they are.
+impl<N: Number> Shrink for Almanac<N> {
+    fn parts(&self) -> usize {
+        self.seeds.len().div_ceil(2)
+    }
+
+    fn without(&self, range: Range<usize>) -> Option<Self> {
+        let end = (range.end * 2).min(self.seeds.len());
+        let seeds = [&self.seeds[..range.start * 2], &self.seeds[end..]].concat();
+
+        (!seeds.is_empty()).then(|| Almanac {
+            seeds,
+            blocks: self.blocks.clone(),
+        })
+    }
+}
+
+impl<N: Number> FromStr for Almanac<N> {
+    type Err = AlmanacError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s.lines().map(str::trim).collect::<Vec<_>>();
+        let mut paragraphs = lines
+            .split(|line| line.is_empty())
+            .filter(|paragraph| !paragraph.is_empty())
+            .map(|paragraph| paragraph.join("\n"));
+
+        let seeds = paragraphs
+            .next()
+            .and_then(|line| line.strip_prefix("seeds:").map(str::to_string))
+            .ok_or(AlmanacError::MissingSeeds)?;
+        let seeds = seeds
+            .split_whitespace()
+            .map(parse_number)
+            .collect::<Result<_, _>>()?;
+
+        let mut by_source = HashMap::new();
+        for paragraph in paragraphs {
+            let block = paragraph.parse::<Block<N>>()?;
+
+            if by_source.contains_key(&block.from) {
+                return Err(AlmanacError::DuplicateSource(block.from));
+            }
+            by_source.insert(block.from.clone(), block);
+        }
+
+        let mut blocks = Vec::new();
+        let mut visited = HashSet::new();
+        let mut category = "seed".to_string();
+
+        while category != "location" {
+            if !visited.insert(category.clone()) {
+                return Err(AlmanacError::Cycle(category));
+            }
+
+            let block = by_source
+                .remove(&category)
+                .ok_or_else(|| AlmanacError::BrokenChain(category.clone()))?;
+
+            category = block.to.clone();
+            blocks.push(block);
+        }
+
+        if !by_source.is_empty() {
+            let mut unused = by_source
+                .values()
+                .map(|block| format!("{}-to-{}", block.from, block.to))
+                .collect::<Vec<_>>();
+            unused.sort();
+
+            return Err(AlmanacError::Unused(unused));
+        }
+
+        Ok(Almanac { seeds, blocks })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        almanac::{Almanac, AlmanacError},
+        tests::EXAMPLE,
+    };
+
+    #[test]
+    fn chain_order_test() {
+        let (seeds, rest) = EXAMPLE.split_once("\n\n").unwrap();
+        let mut blocks = rest.split("\n\n").collect::<Vec<_>>();
+        blocks.reverse();
+        let shuffled = format!("{seeds}\n\n{}", blocks.join("\n\n"));
+
+        let almanac = shuffled.parse::<Almanac<u64>>().unwrap();
+        let chain = almanac
+            .blocks
+            .iter()
+            .map(|block| block.to.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            chain,
+            vec![
+                "soil",
+                "fertilizer",
+                "water",
+                "light",
+                "temperature",
+                "humidity",
+                "location"
+            ]
+        );
+        assert_eq!(almanac.seeds, vec![79, 14, 55, 13]);
+    }
+
+    #[test]
+    fn convert_test() {
+        let almanac = EXAMPLE.parse::<Almanac<u64>>().unwrap();
+
+        assert_eq!(almanac.convert("soil", "humidity", 84), Ok(46));
+        assert_eq!(almanac.convert("seed", "location", 82), Ok(46));
+        assert_eq!(almanac.convert("water", "water", 7), Ok(7));
+        assert_eq!(
+            almanac.convert("humidity", "soil", 46),
+            Err(AlmanacError::NoConversion {
+                from: "humidity".to_string(),
+                to: "soil".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn seed_ranges_test() {
+        let almanac = EXAMPLE.parse::<Almanac<u64>>().unwrap();
+        assert_eq!(almanac.seed_ranges(), Ok(vec![79..93, 55..68]));
+
+        let odd = "seeds: 79 14 55\n\nseed-to-location map:";
+        assert_eq!(
+            odd.parse::<Almanac<u64>>().unwrap().seed_ranges(),
+            Err(AlmanacError::UnpairedSeed("55".to_string()))
+        );
+    }
+
+    #[test]
+    fn chain_errors_test() {
+        let broken = "seeds: 1 2\n\nseed-to-soil map:\n1 2 3\n\nwater-to-location map:\n1 2 3";
+        assert_eq!(
+            broken.parse::<Almanac<u64>>().unwrap_err(),
+            AlmanacError::BrokenChain("soil".to_string())
+        );
+
+        let duplicate = "seeds: 1\n\nseed-to-soil map:\n\nseed-to-water map:";
+        assert_eq!(
+            duplicate.parse::<Almanac<u64>>().unwrap_err(),
+            AlmanacError::DuplicateSource("seed".to_string())
+        );
+
+        let cycle = "seeds: 1\n\nseed-to-soil map:\n\nsoil-to-seed map:";
+        assert_eq!(
+            cycle.parse::<Almanac<u64>>().unwrap_err(),
+            AlmanacError::Cycle("seed".to_string())
+        );
+
+        let unused = "seeds: 1\n\nseed-to-location map:\n\nsoil-to-water map:";
+        assert_eq!(
+            unused.parse::<Almanac<u64>>().unwrap_err(),
+            AlmanacError::Unused(vec!["soil-to-water".to_string()])
+        );
+
+        let header = "seeds: 1\n\nseed to location:\n1 2 3";
+        assert_eq!(
+            header.parse::<Almanac<u64>>().unwrap_err(),
+            AlmanacError::InvalidHeader("seed to location:".to_string())
+        );
+    }
+}
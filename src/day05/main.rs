@@ -1,79 +1,331 @@
-#![feature(iter_advance_by)]
-#![feature(iter_array_chunks)]
-
-use std::ops::Range;
+use std::{env, ops::Range, process};
 
 use std::cmp::Ordering::*;
 
+use aoc::alternates::Alternates;
+
+use crate::{
+    almanac::{Almanac, AlmanacError},
+    number::Number,
+    path::CategoryPath,
+    validate::validate,
+    verify::{lowest_location_parallel, CHUNK},
+};
+
+mod almanac;
+mod number;
+mod path;
+mod svg;
+mod validate;
+mod verify;
+
 fn main() {
     let x = std::fs::read_to_string("input/day05_test.txt").unwrap();
 
-    let solution = get_part_1(x.clone());
+    let almanac = match x.parse::<Almanac<u64>>() {
+        Ok(almanac) => almanac,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
+
+    let reports = match validate(&almanac) {
+        Ok(reports) => reports,
+        Err(e) => {
+            let reports = almanac.blocks.iter().map(validate::BlockReport::new);
+            for report in reports.filter(|report| report.has_overlaps()) {
+                eprint!("{report}");
+            }
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
 
-    println!("part 1: {}", solution);
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.first().is_some_and(|command| command == "validate") {
+        for report in &reports {
+            print!("{report}");
+        }
+        return;
+    }
 
-    let sol2 = get_part_2(x);
+    for report in reports.iter().filter(|report| report.has_overlaps()) {
+        eprint!("{report}");
+    }
 
-    println!("part 2: {}", sol2);
-}
+    if let [command, from, to, values @ ..] = &args[..] {
+        if command == "convert" {
+            print_conversions(&almanac, from, to, values);
+            return;
+        }
+    }
 
-fn get_part_1(input: String) -> i128 {
-    let (first_line, rest) = input.split_once("\n\n").unwrap();
+    if args.first().is_some_and(|command| command == "svg") {
+        match svg::render(&almanac) {
+            Ok(svg) => print!("{svg}"),
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        }
+        return;
+    }
 
-    let mut ids = first_line.split(" ");
+    if args.first().is_some_and(|command| command == "stats") {
+        match layers(&almanac) {
+            Ok(layers) => print_stats(&layers),
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        }
+        return;
+    }
 
-    ids.advance_by(1).unwrap();
+    if args.first().is_some_and(|command| command == "verify") {
+        verify(&almanac);
+        return;
+    }
 
-    let mut seeds = ids
-        .map(|x| x.parse::<i128>().unwrap())
-        .collect::<Vec<i128>>();
+    if args.first().is_some_and(|command| command == "check") {
+        match part_2_alternates().run(&almanac) {
+            Ok(Ok(location)) => println!("part 2 implementations agree: {location}"),
+            Ok(Err(e)) => {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+            Err(divergence) => {
+                eprint!("{divergence}");
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let path = match get_part_1_path(&almanac) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
 
-    for block in rest.split("\n\n") {
-        let ranges = parse_block(block);
+    println!("part 1: {}", path.location());
+    println!("\t{path}");
 
-        for sed in &mut seeds {
-            for range in &ranges {
-                if range.source.contains(sed) {
-                    *sed = range.dest.start + (*sed - range.source.start);
-                    break;
-                }
+    let path = match get_part_2_path(&almanac) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
+
+    println!("part 2: {}", path.location());
+    println!("\t{path}");
+}
+
+fn print_conversions(almanac: &Almanac<u64>, from: &str, to: &str, values: &[String]) {
+    for value in values {
+        let converted = value
+            .parse::<u64>()
+            .map_err(|_| format!("invalid number {value}"))
+            .and_then(|value| almanac.convert(from, to, value).map_err(|e| e.to_string()));
+
+        match converted {
+            Ok(converted) => println!("{from} {value} -> {to} {converted}"),
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(1);
             }
         }
     }
-
-    *seeds.iter().min().unwrap()
 }
 
-fn get_part_2(input: String) -> i128 {
-    let (first_line, rest) = input.split_once("\n\n").unwrap();
+fn print_stats<N: Number>(layers: &[Layer<N>]) {
+    println!("{:<12} {:>8} {:>8}", "category", "split", "merged");
 
-    let mut ids = first_line.split(" ");
+    for layer in layers {
+        println!(
+            "{:<12} {:>8} {:>8}",
+            layer.category,
+            layer.split,
+            layer.pieces.len()
+        );
+    }
+}
 
-    ids.advance_by(1).unwrap();
+/// Compares part 2 against mapping every seed on its own, for inputs too
+/// large for the brute force of [`part_2_alternates`].
+fn verify(almanac: &Almanac<u64>) {
+    let result = get_part_2(almanac).and_then(|splitting| {
+        let brute_force = lowest_location_parallel(almanac, CHUNK, |done, total| {
+            eprint!("\rmapped {done}/{total} chunks ({}%)", done * 100 / total);
+        })?;
+        eprintln!();
+
+        Ok((splitting, brute_force))
+    });
+
+    match result {
+        Ok((splitting, Some(brute_force))) => {
+            println!("range splitting: {splitting}");
+            println!("brute force:     {brute_force}");
+
+            if splitting != brute_force {
+                eprintln!("part 2 results differ");
+                process::exit(1);
+            }
+        }
+        Ok((_, None)) => {
+            eprintln!("no seeds to verify");
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    }
+}
 
-    let mut seeds = ids
-        .array_chunks::<2>()
-        .map(|x| {
-            let start = x[0].parse::<i128>().unwrap();
-            let end_after = x[1].parse::<i128>().unwrap();
-            start..start + end_after
-        })
-        .collect::<Vec<Range<i128>>>();
+/// Range splitting against the brute force, shrinking the seed ranges
+/// down to the ones they disagree on. Only for small inputs.
+fn part_2_alternates() -> Alternates<'static, Result<u64, AlmanacError>, Almanac<u64>> {
+    Alternates::new("part 2")
+        .register("range splitting", get_part_2)
+        .register("brute force", get_part_2_brute_force)
+}
 
-    let blocks = rest.split("\n\n");
+/// The path of the seed with the lowest location, the lowest such seed on
+/// ties.
+fn get_part_1_path<N: Number>(almanac: &Almanac<N>) -> Result<CategoryPath<N>, AlmanacError> {
+    almanac
+        .seeds
+        .iter()
+        .map(|seed| CategoryPath::new(*seed, &almanac.blocks))
+        .min_by_key(|path| (path.location(), path.seed()))
+        .ok_or(AlmanacError::NoSeeds)
+}
 
-    for block in blocks {
-        let ranges = parse_block(block);
-        seeds = get_schnitt(seeds, ranges);
+/// Maps every single seed through all blocks.
+fn get_part_2_brute_force<N: Number>(almanac: &Almanac<N>) -> Result<N, AlmanacError> {
+    let mut lowest = None;
+
+    for range in almanac.seed_ranges()? {
+        let mut seed = range.start;
+        while seed < range.end {
+            let location = almanac
+                .blocks
+                .iter()
+                .fold(seed, |value, block| lookup(&block.ranges, value));
+            lowest = Some(lowest.map_or(location, |lowest: N| lowest.min(location)));
+            seed = seed + N::ONE;
+        }
     }
-    seeds
+
+    lowest.ok_or(AlmanacError::NoSeeds)
+}
+
+/// Maps a single value through one block.
+fn lookup<N: Number>(ranges: &[MapSingle<N>], value: N) -> N {
+    ranges
         .iter()
-        .min_by(|a, b| a.start.cmp(&b.start))
-        .unwrap()
-        .start
+        .find(|range| range.source.contains(&value))
+        .map_or(value, |range| range.map(value))
 }
 
-fn get_schnitt(mut source: Vec<Range<i128>>, map: Vec<MapSingle>) -> Vec<Range<i128>> {
+fn get_part_2<N: Number>(almanac: &Almanac<N>) -> Result<N, AlmanacError> {
+    Ok(get_part_2_path(almanac)?.location())
+}
+
+/// Like [`get_part_1_path`], for seed ranges. Every range remembers the seed
+/// its start came from, so the seed of the lowest location can be recovered.
+fn get_part_2_path<N: Number>(almanac: &Almanac<N>) -> Result<CategoryPath<N>, AlmanacError> {
+    let layers = layers(almanac)?;
+
+    // pieces are sorted and disjoint, so the first one holds the lowest location
+    let (_, seed) = layers
+        .last()
+        .and_then(|locations| locations.pieces.first())
+        .ok_or(AlmanacError::NoSeeds)?;
+
+    Ok(CategoryPath::new(*seed, &almanac.blocks))
+}
+
+/// The pieces the seed ranges are split into in one category.
+struct Layer<N> {
+    category: String,
+    /// How many pieces [`get_schnitt`] left before they were normalised
+    split: usize,
+    pieces: Vec<Tracked<N>>,
+}
+
+/// The seed ranges, followed by what [`get_schnitt`] makes of them in every
+/// block, normalised after each one.
+fn layers<N: Number>(almanac: &Almanac<N>) -> Result<Vec<Layer<N>>, AlmanacError> {
+    let seeds = almanac
+        .seed_ranges()?
+        .into_iter()
+        .map(|range| (range.clone(), range.start))
+        .collect::<Vec<Tracked<N>>>();
+
+    let mut layers = vec![Layer {
+        category: "seed".to_string(),
+        split: seeds.len(),
+        pieces: normalise(seeds),
+    }];
+
+    for block in &almanac.blocks {
+        let split = get_schnitt(layers.last().unwrap().pieces.clone(), &block.ranges);
+
+        layers.push(Layer {
+            category: block.to.clone(),
+            split: split.len(),
+            pieces: normalise(split),
+        });
+    }
+
+    Ok(layers)
+}
+
+/// A range together with the seed its start came from.
+type Tracked<N> = (Range<N>, N);
+
+/// Sorts the pieces and drops empty ones and values already covered by a
+/// piece starting lower, so the result is disjoint. Touching or overlapping
+/// pieces which continue the same run of seeds are merged.
+fn normalise<N: Number>(mut pieces: Vec<Tracked<N>>) -> Vec<Tracked<N>> {
+    pieces.retain(|(range, _)| !range.is_empty());
+    pieces.sort_by_key(|(range, seed)| (range.start, *seed));
+
+    let mut result: Vec<Tracked<N>> = Vec::new();
+
+    for (range, seed) in pieces {
+        let Some((last, last_seed)) = result.last_mut() else {
+            result.push((range, seed));
+            continue;
+        };
+
+        // a piece pushed after trimming can start after the ones following it
+        let continues = range.start >= last.start
+            && seed >= *last_seed
+            && seed - *last_seed == range.start - last.start;
+
+        if range.start > last.end {
+            result.push((range, seed));
+        } else if continues {
+            last.end = last.end.max(range.end);
+        } else if range.end > last.end {
+            let start = last.end;
+            result.push((start..range.end, seed + (start - range.start)));
+        }
+    }
+
+    result
+}
+
+fn get_schnitt<N: Number>(mut source: Vec<Tracked<N>>, map: &[MapSingle<N>]) -> Vec<Tracked<N>> {
     let mut i = 0;
 
     let mut result = Vec::new();
@@ -81,44 +333,40 @@ fn get_schnitt(mut source: Vec<Range<i128>>, map: Vec<MapSingle>) -> Vec<Range<i
     while i < source.len() {
         let mut found_range = false;
 
-        for map_single in &map {
-            let cmp = source[i].compare(&map_single.source);
+        for map_single in map {
+            let (range, seed) = source[i].clone();
+            let cmp = range.compare(&map_single.source);
 
             let found = match cmp.get_matching_part() {
                 Some(x) => x,
                 None => continue,
             };
 
-            let space_front = found.start - map_single.source.start;
-            let length = found.end - found.start;
-
-            let found =
-                map_single.dest.start + space_front..map_single.dest.start + space_front + length;
-
             found_range = true;
-            result.push(found);
+            let seed_of = |part: &Range<N>| seed + (part.start - range.start);
+            result.push((map_single.map_range(&found), seed_of(&found)));
 
+            // The rest may still hit other entries, so it goes through the map again
             let left = cmp.get_original_not_matching_parts();
-            source.extend(left);
+            source.extend(left.into_iter().map(|part| (part.clone(), seed_of(&part))));
+            break;
         }
 
         if !found_range {
             result.push(source[i].clone());
         }
-        i = i + 1;
+        i += 1;
     }
 
     result
 }
 
-trait RangeExt {
-    fn compare(&self, other: &Range<i128>) -> RangeCmpResult;
-
-    fn shift(&self, shift: i128) -> Range<i128>;
+trait RangeExt<N> {
+    fn compare(&self, other: &Range<N>) -> RangeCmpResult<N>;
 }
 
-impl RangeExt for Range<i128> {
-    fn compare(&self, other: &Range<i128>) -> RangeCmpResult {
+impl<N: Number> RangeExt<N> for Range<N> {
+    fn compare(&self, other: &Range<N>) -> RangeCmpResult<N> {
         if self.is_empty() || other.is_empty() {
             // when empty always not included
             return RangeCmpResult::NotIncluded;
@@ -130,40 +378,39 @@ impl RangeExt for Range<i128> {
             self.start.cmp(&other.end),
             self.end.cmp(&other.start),
         ) {
-            (Equal, Equal, _, _) => RangeCmpResult::CompletelyTheSame,
+            (Equal, Equal, _, _) => RangeCmpResult::CompletelyTheSame {
+                original_included_part: self.start..self.end,
+            },
             // Greater or Equal because the range is exclusive above
             (_, _, Greater | Equal, _) => RangeCmpResult::NotIncluded,
             (_, _, _, Less | Equal) => RangeCmpResult::NotIncluded,
             (Less, Less, _, _) => RangeCmpResult::EndIndluded {
-                // + 1 because the range is inclusive at the beginning
-                other_after: self.end + 1..other.end,
+                other_after: self.end..other.end,
                 original_part_which_is_not_included: self.start..other.start,
                 original_included_part: other.start..self.end,
             },
             (Greater, Greater, _, _) => RangeCmpResult::StartIncluded {
                 other_before: other.start..self.start,
-                // + 1 because the range is inclusive at the beginning
-                original_part_which_is_not_included: other.end + 1..self.end,
+                original_part_which_is_not_included: other.end..self.end,
                 original_included_part: self.start..other.end,
             },
             (Less, Greater, _, _) => RangeCmpResult::MiddleIncluded {
                 original_included_part: other.start..other.end,
                 original_before_not_included: self.start..other.start,
-                // +1 because the range is inclusive at the beginning
-                original_after_not_included: other.end + 1..self.end,
+                original_after_not_included: other.end..self.end,
             },
             (Greater, Less, _, _) => RangeCmpResult::CompletelyIncluded {
                 other_before: other.start..self.start,
-                other_after: self.end + 1..other.end,
+                other_after: self.end..other.end,
                 original_included_part: self.start..self.end,
             },
             (Equal, Less, _, _) => RangeCmpResult::SameStartOriginalShorter {
                 original_included_part: self.start..self.end,
-                other_after_not_included: self.end + 1..other.end,
+                other_after_not_included: self.end..other.end,
             },
             (Equal, Greater, _, _) => RangeCmpResult::SameStartOtherShorter {
                 original_included_part: other.start..other.end,
-                original_after_not_included: other.end + 1..self.end,
+                original_after_not_included: other.end..self.end,
             },
             (Less, Equal, _, _) => RangeCmpResult::SameEndOtherShorter {
                 original_included_part: other.start..other.end,
@@ -175,58 +422,61 @@ impl RangeExt for Range<i128> {
             },
         }
     }
-    fn shift(&self, shift: i128) -> Range<i128> {
-        self.start + shift..self.end + shift
-    }
 }
 
+// The parts of `other` are only needed to describe a comparison completely
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
-enum RangeCmpResult {
-    CompletelyTheSame,
+enum RangeCmpResult<N> {
+    CompletelyTheSame {
+        original_included_part: Range<N>,
+    },
     NotIncluded,
     CompletelyIncluded {
-        other_before: Range<i128>,
-        other_after: Range<i128>,
-        original_included_part: Range<i128>,
+        other_before: Range<N>,
+        other_after: Range<N>,
+        original_included_part: Range<N>,
     },
     EndIndluded {
         // The "rest" from the other range which is not included on the original one
-        other_after: Range<i128>,
-        original_part_which_is_not_included: Range<i128>,
-        original_included_part: Range<i128>,
+        other_after: Range<N>,
+        original_part_which_is_not_included: Range<N>,
+        original_included_part: Range<N>,
     },
     StartIncluded {
-        other_before: Range<i128>,
-        original_part_which_is_not_included: Range<i128>,
-        original_included_part: Range<i128>,
+        other_before: Range<N>,
+        original_part_which_is_not_included: Range<N>,
+        original_included_part: Range<N>,
     },
     MiddleIncluded {
-        original_included_part: Range<i128>,
-        original_before_not_included: Range<i128>,
-        original_after_not_included: Range<i128>,
+        original_included_part: Range<N>,
+        original_before_not_included: Range<N>,
+        original_after_not_included: Range<N>,
     },
     SameStartOriginalShorter {
-        original_included_part: Range<i128>,
-        other_after_not_included: Range<i128>,
+        original_included_part: Range<N>,
+        other_after_not_included: Range<N>,
     },
     SameStartOtherShorter {
-        original_included_part: Range<i128>,
-        original_after_not_included: Range<i128>,
+        original_included_part: Range<N>,
+        original_after_not_included: Range<N>,
     },
     SameEndOriginalShorter {
-        original_included_part: Range<i128>,
-        other_before_not_included: Range<i128>,
+        original_included_part: Range<N>,
+        other_before_not_included: Range<N>,
     },
     SameEndOtherShorter {
-        original_included_part: Range<i128>,
-        original_before_not_included: Range<i128>,
+        original_included_part: Range<N>,
+        original_before_not_included: Range<N>,
     },
 }
 
-impl RangeCmpResult {
-    fn get_matching_part(&self) -> Option<Range<i128>> {
+impl<N: Number> RangeCmpResult<N> {
+    fn get_matching_part(&self) -> Option<Range<N>> {
         match self {
-            RangeCmpResult::CompletelyTheSame => None,
+            RangeCmpResult::CompletelyTheSame {
+                original_included_part,
+            } => Some(original_included_part.clone()),
             RangeCmpResult::NotIncluded => None,
             RangeCmpResult::CompletelyIncluded {
                 other_before: _,
@@ -268,9 +518,11 @@ impl RangeCmpResult {
         }
     }
 
-    fn get_original_not_matching_parts(&self) -> Vec<Range<i128>> {
+    fn get_original_not_matching_parts(&self) -> Vec<Range<N>> {
         match self {
-            RangeCmpResult::CompletelyTheSame => vec![],
+            RangeCmpResult::CompletelyTheSame {
+                original_included_part: _,
+            } => vec![],
             RangeCmpResult::NotIncluded => vec![],
             RangeCmpResult::CompletelyIncluded {
                 other_before: _,
@@ -298,7 +550,7 @@ impl RangeCmpResult {
             ],
             RangeCmpResult::SameStartOriginalShorter {
                 original_included_part: _,
-                other_after_not_included,
+                other_after_not_included: _,
             } => vec![],
             RangeCmpResult::SameStartOtherShorter {
                 original_included_part: _,
@@ -306,7 +558,7 @@ impl RangeCmpResult {
             } => vec![original_after_not_included.clone()],
             RangeCmpResult::SameEndOriginalShorter {
                 original_included_part: _,
-                other_before_not_included,
+                other_before_not_included: _,
             } => vec![],
             RangeCmpResult::SameEndOtherShorter {
                 original_included_part: _,
@@ -316,28 +568,314 @@ impl RangeCmpResult {
     }
 }
 
-struct MapSingle {
-    source: Range<i128>,
-    dest: Range<i128>,
+#[derive(Debug, Clone)]
+struct MapSingle<N> {
+    source: Range<N>,
+    dest: Range<N>,
 }
 
-fn parse_block(block: &str) -> Vec<MapSingle> {
-    let mut lines = block.lines();
+impl<N: Number> MapSingle<N> {
+    /// `None` if either range does not fit into `N`. Afterwards nothing
+    /// inside `source` can map past the end of `dest`, so mapping needs no
+    /// more checks.
+    fn new(dest: N, source: N, length: N) -> Option<Self> {
+        Some(MapSingle {
+            source: source..source.checked_add(length)?,
+            dest: dest..dest.checked_add(length)?,
+        })
+    }
 
-    lines.advance_by(1).unwrap();
+    fn map(&self, value: N) -> N {
+        self.dest.start + (value - self.source.start)
+    }
 
-    lines
-        .map(|x| {
-            let x = x.split(" ");
-            let mut x = x.map(|x| x.parse::<i128>().unwrap());
+    /// Maps a part of `source`.
+    fn map_range(&self, part: &Range<N>) -> Range<N> {
+        let start = self.map(part.start);
+        start..start + (part.end - part.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeSet, ops::Range};
 
-            let dest = x.next().unwrap();
-            let source = x.next().unwrap();
-            let length = x.next().unwrap();
+    use proptest::{prelude::*, test_runner::FileFailurePersistence};
 
-            let source = source..source + length;
-            let dest = dest..dest + length;
-            MapSingle { source, dest }
+    use crate::{
+        almanac::{Almanac, AlmanacError},
+        get_part_1_path, get_part_2, get_part_2_brute_force, get_part_2_path, get_schnitt, lookup,
+        normalise, part_2_alternates, MapSingle, RangeExt,
+    };
+
+    fn parse(input: &str) -> Almanac<u64> {
+        input.parse().unwrap()
+    }
+
+    pub const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn part_1_test() {
+        assert_eq!(get_part_1_path(&parse(EXAMPLE)).unwrap().location(), 35);
+    }
+
+    #[test]
+    fn part_2_test() {
+        assert_eq!(part_2_alternates().check(&parse(EXAMPLE)), Ok(46));
+    }
+
+    #[test]
+    fn no_seeds_test() {
+        let none = parse("seeds:\n\nseed-to-location map:\n1 2 3");
+        assert_eq!(get_part_1_path(&none).unwrap_err(), AlmanacError::NoSeeds);
+        assert_eq!(get_part_2_path(&none).unwrap_err(), AlmanacError::NoSeeds);
+
+        let empty = parse("seeds: 5 0 7 0\n\nseed-to-location map:\n1 2 3");
+        assert!(get_part_1_path(&empty).is_ok());
+        assert_eq!(get_part_2_path(&empty).unwrap_err(), AlmanacError::NoSeeds);
+        assert_eq!(get_part_2_brute_force(&empty), Err(AlmanacError::NoSeeds));
+    }
+
+    #[test]
+    fn display_test() {
+        assert_eq!(parse(EXAMPLE).to_string(), EXAMPLE);
+    }
+
+    #[test]
+    fn path_test() {
+        let path = get_part_1_path(&parse(EXAMPLE)).unwrap();
+        assert_eq!(
+            path.to_string(),
+            "seed 13 -> soil 13 -> fertilizer 52 -> water 41 -> light 34 -> temperature 34 -> humidity 35 -> location 35"
+        );
+
+        let path = get_part_2_path(&parse(EXAMPLE)).unwrap();
+        assert_eq!(path.seed(), 82);
+        assert_eq!(
+            path.stages
+                .iter()
+                .map(|(_, value)| *value)
+                .collect::<Vec<_>>(),
+            vec![82, 84, 84, 84, 77, 45, 46, 46]
+        );
+    }
+
+    #[test]
+    fn normalise_test() {
+        let pieces = vec![
+            (10u64..20, 0),
+            (0..5, 50),
+            (5..8, 55),
+            (15..30, 100),
+            (40..40, 7),
+        ];
+
+        assert_eq!(
+            normalise(pieces),
+            vec![(0..8, 50), (10..20, 0), (20..30, 105)]
+        );
+    }
+
+    #[test]
+    fn overflow_test() {
+        let almanac = "seeds: 4294967290 5\n\nseed-to-location map:\n0 4294967290 5"
+            .parse::<Almanac<u32>>()
+            .unwrap();
+        assert_eq!(get_part_2(&almanac), Ok(0));
+        assert_eq!(get_part_2_brute_force(&almanac), Ok(0));
+
+        let seeds = "seeds: 4294967290 6\n\nseed-to-location map:";
+        assert_eq!(
+            get_part_2(&seeds.parse::<Almanac<u32>>().unwrap()),
+            Err(AlmanacError::Overflow("seeds: 4294967290 6".to_string()))
+        );
+
+        let entry = "seeds: 1 2\n\nseed-to-location map:\n4294967290 0 10";
+        assert_eq!(
+            entry.parse::<Almanac<u32>>().unwrap_err(),
+            AlmanacError::Overflow("4294967290 0 10".to_string())
+        );
+        assert!(entry.parse::<Almanac<u64>>().is_ok());
+    }
+
+    fn range() -> impl Strategy<Value = Range<i128>> {
+        (0i128..100, 0i128..30).prop_map(|(start, length)| start..start + length)
+    }
+
+    /// Entries with non-overlapping sources, as in the puzzle input.
+    fn block() -> impl Strategy<Value = Vec<MapSingle<i128>>> {
+        prop::collection::vec((0i128..10, 1i128..20, 0i128..150), 0..5).prop_map(|entries| {
+            let mut start = 0;
+            entries
+                .into_iter()
+                .map(|(gap, length, dest)| {
+                    start += gap;
+                    let source = start..start + length;
+                    start += length;
+                    MapSingle {
+                        source,
+                        dest: dest..dest + length,
+                    }
+                })
+                .collect()
         })
-        .collect()
+    }
+
+    fn block_text(from: &str, to: &str, map: &[MapSingle<i128>]) -> String {
+        let mut text = format!("{from}-to-{to} map:");
+        for m in map {
+            text += &format!(
+                "\n{} {} {}",
+                m.dest.start,
+                m.source.start,
+                m.source.end - m.source.start
+            );
+        }
+        text
+    }
+
+    proptest! {
+        // every binary has a main.rs, so the default file next to it would be shared
+        #![proptest_config(ProptestConfig {
+            cases: 2000,
+            failure_persistence: Some(Box::new(FileFailurePersistence::Direct(
+                "src/day05/proptest-regressions.txt",
+            ))),
+            ..ProptestConfig::default()
+        })]
+
+        #[test]
+        fn compare_splits_original(a in range(), b in range()) {
+            let cmp = a.compare(&b);
+
+            let included = cmp.get_matching_part().unwrap_or(0..0);
+            let expected = a.clone().filter(|x| b.contains(x)).collect::<Vec<_>>();
+            prop_assert_eq!(included.clone().collect::<Vec<_>>(), expected);
+
+            let mut parts = cmp
+                .get_original_not_matching_parts()
+                .into_iter()
+                .chain([included])
+                .flatten()
+                .collect::<Vec<_>>();
+            parts.sort();
+
+            if parts.is_empty() {
+                // nothing included means the original is left alone
+                prop_assert!(a.is_empty() || a.clone().all(|x| !b.contains(&x)));
+            } else {
+                prop_assert_eq!(parts, a.collect::<Vec<_>>());
+            }
+        }
+
+        #[test]
+        fn normalise_keeps_values(
+            pieces in prop::collection::vec((range(), 0i128..100), 0..8),
+        ) {
+            let values = |pieces: &[(Range<i128>, i128)]| {
+                pieces
+                    .iter()
+                    .flat_map(|(range, seed)| range.clone().map(move |v| (v, seed + v - range.start)))
+                    .collect::<BTreeSet<_>>()
+            };
+
+            let normalised = normalise(pieces.clone());
+
+            for pair in normalised.windows(2) {
+                prop_assert!(pair[0].0.end <= pair[1].0.start);
+            }
+
+            let before = values(&pieces);
+            let after = values(&normalised);
+            prop_assert!(after.is_subset(&before));
+            prop_assert_eq!(
+                after.iter().map(|(v, _)| *v).collect::<BTreeSet<_>>(),
+                before.iter().map(|(v, _)| *v).collect::<BTreeSet<_>>()
+            );
+        }
+
+        #[test]
+        fn schnitt_agrees_with_lookup(
+            seeds in prop::collection::vec(range(), 1..5),
+            map in block(),
+        ) {
+            let expected = seeds
+                .iter()
+                .flat_map(|r| r.clone())
+                .map(|seed| lookup(&map, seed))
+                .collect::<BTreeSet<_>>();
+
+            let seeds = seeds.into_iter().map(|range| (range.clone(), range.start)).collect();
+            let mapped = get_schnitt(seeds, &map);
+
+            for (range, seed) in &mapped {
+                for value in range.clone() {
+                    prop_assert_eq!(lookup(&map, seed + (value - range.start)), value);
+                }
+            }
+
+            let mapped = mapped
+                .into_iter()
+                .flat_map(|(range, _)| range)
+                .collect::<BTreeSet<_>>();
+            prop_assert_eq!(mapped, expected);
+        }
+
+        #[test]
+        fn part_2_agrees_with_brute_force(
+            seeds in prop::collection::vec((0i128..100, 1i128..30), 1..4),
+            blocks in prop::collection::vec(block(), 1..4),
+        ) {
+            let seeds = seeds
+                .iter()
+                .map(|(start, length)| format!("{start} {length}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let mut categories = (0..blocks.len()).map(|i| format!("category{i}")).collect::<Vec<_>>();
+            categories[0] = "seed".to_string();
+            categories.push("location".to_string());
+
+            let blocks = blocks
+                .iter()
+                .enumerate()
+                .map(|(i, b)| block_text(&categories[i], &categories[i + 1], b))
+                .collect::<Vec<_>>();
+            let input = format!("seeds: {seeds}\n\n{}", blocks.join("\n\n"));
+
+            let result = part_2_alternates().run(&parse(&input));
+            prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+        }
+    }
 }
@@ -0,0 +1,52 @@
+use std::{
+    fmt,
+    ops::{Add, Sub},
+    str::FromStr,
+};
+
+/// The integer types an almanac can be read into.
+///
+/// A value is only ever subtracted from a larger one, and every sum is
+/// either checked or known to stay below the end of a checked range, so
+/// unsigned types work just as well as signed ones.
+pub trait Number:
+    Copy
+    + Ord
+    + Send
+    + Sync
+    + fmt::Debug
+    + fmt::Display
+    + FromStr
+    + From<u32>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// For drawing, where precision does not matter.
+    fn as_f64(self) -> f64;
+}
+
+macro_rules! number {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn as_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+number!(u32, u64, i128);
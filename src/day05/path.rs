@@ -0,0 +1,45 @@
+use std::fmt;
+
+use crate::{almanac::Block, lookup, number::Number};
+
+/// The value of a seed in every category, from the seed itself up to its
+/// location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryPath<N> {
+    pub stages: Vec<(String, N)>,
+}
+
+impl<N: Number> CategoryPath<N> {
+    /// Follows `seed` through the chain of `blocks`.
+    pub fn new(seed: N, blocks: &[Block<N>]) -> Self {
+        let mut stages = vec![("seed".to_string(), seed)];
+        let mut value = seed;
+
+        for block in blocks {
+            value = lookup(&block.ranges, value);
+            stages.push((block.to.clone(), value));
+        }
+
+        CategoryPath { stages }
+    }
+
+    pub fn seed(&self) -> N {
+        self.stages[0].1
+    }
+
+    pub fn location(&self) -> N {
+        self.stages.last().unwrap().1
+    }
+}
+
+impl<N: Number> fmt::Display for CategoryPath<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stages = self
+            .stages
+            .iter()
+            .map(|(category, value)| format!("{category} {value}"))
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", stages.join(" -> "))
+    }
+}
@@ -0,0 +1,204 @@
+use std::{fmt::Write, ops::Range};
+
+use crate::{
+    almanac::{Almanac, AlmanacError},
+    layers,
+    number::Number,
+    RangeExt, Tracked,
+};
+
+const SEED_COLORS: [&str; 6] = [
+    "crimson",
+    "darkorange",
+    "gold",
+    "forestgreen",
+    "royalblue",
+    "purple",
+];
+
+const MARGIN: f64 = 40.0;
+const HEIGHT: f64 = 600.0;
+const COLUMN_WIDTH: f64 = 16.0;
+const COLUMN_GAP: f64 = 160.0;
+
+struct Scale {
+    max: f64,
+}
+
+impl Scale {
+    fn y<N: Number>(&self, value: N) -> f64 {
+        MARGIN + value.as_f64() / self.max * HEIGHT
+    }
+
+    fn x(&self, column: usize) -> f64 {
+        MARGIN + column as f64 * (COLUMN_WIDTH + COLUMN_GAP)
+    }
+
+    /// A band from `from` on the right edge of `column` to `to` on the left
+    /// edge of the next one.
+    fn band<N: Number>(&self, column: usize, from: &Range<N>, to: &Range<N>) -> String {
+        let left = self.x(column) + COLUMN_WIDTH;
+        let right = self.x(column + 1);
+
+        format!(
+            "{left:.1},{:.1} {right:.1},{:.1} {right:.1},{:.1} {left:.1},{:.1}",
+            self.y(from.start),
+            self.y(to.start),
+            self.y(to.end),
+            self.y(from.end)
+        )
+    }
+}
+
+/// Pairs of where values of `piece` were one layer before and where they
+/// are now, `layer` being the pieces of that layer. Merged pieces can come
+/// from several pieces of the layer before.
+fn bands<N: Number>(piece: &Tracked<N>, layer: &[Tracked<N>]) -> Vec<(Range<N>, Range<N>)> {
+    let (range, seed) = piece;
+    let seeds = *seed..*seed + (range.end - range.start);
+
+    layer
+        .iter()
+        .filter_map(|(before, first)| {
+            let common = (*first..*first + (before.end - before.start))
+                .compare(&seeds)
+                .get_matching_part()?;
+            let length = common.end - common.start;
+
+            let from = before.start + (common.start - *first);
+            let to = range.start + (common.start - *seed);
+            Some((from..from + length, to..to + length))
+        })
+        .collect()
+}
+
+/// Renders the almanac as an SVG with one column per category. The entries
+/// of every block are drawn as grey bands from their source to their
+/// destination, the seed ranges of part 2 as colored bands on top, showing
+/// where [`crate::get_schnitt`] splits and shifts them. Every column is
+/// labelled with the number of pieces the seed ranges are split into after
+/// normalising.
+pub fn render<N: Number>(almanac: &Almanac<N>) -> Result<String, AlmanacError> {
+    let layers = layers(almanac)?;
+    let seed_ranges = almanac.seed_ranges()?;
+
+    let ends = almanac
+        .blocks
+        .iter()
+        .flat_map(|block| &block.ranges)
+        .flat_map(|range| [range.source.end, range.dest.end])
+        .chain(
+            layers
+                .iter()
+                .flat_map(|layer| &layer.pieces)
+                .map(|(range, _)| range.end),
+        );
+    let max = ends.max().map_or(1.0, |max| max.as_f64().max(1.0));
+    let scale = Scale { max };
+
+    // room for the label of the last column
+    let width = scale.x(layers.len() - 1) + COLUMN_GAP;
+    let height = 2.0 * MARGIN + HEIGHT;
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.0}\" height=\"{height:.0}\" font-family=\"monospace\" font-size=\"12\">"
+    )
+    .unwrap();
+
+    for (column, layer) in layers.iter().enumerate() {
+        let x = scale.x(column);
+        writeln!(
+            out,
+            "  <rect x=\"{x:.1}\" y=\"{MARGIN:.1}\" width=\"{COLUMN_WIDTH}\" height=\"{HEIGHT}\" fill=\"whitesmoke\" stroke=\"grey\"/>"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "  <text x=\"{x:.1}\" y=\"{:.1}\">{} ({})</text>",
+            MARGIN / 2.0,
+            layer.category,
+            layer.pieces.len()
+        )
+        .unwrap();
+    }
+
+    for (column, block) in almanac.blocks.iter().enumerate() {
+        for range in &block.ranges {
+            writeln!(
+                out,
+                "  <polygon points=\"{}\" fill=\"grey\" fill-opacity=\"0.3\"><title>{} {} {}</title></polygon>",
+                scale.band(column, &range.source, &range.dest),
+                range.dest.start,
+                range.source.start,
+                range.source.end - range.source.start
+            )
+            .unwrap();
+        }
+    }
+
+    let color = |seed: &N| {
+        let index = seed_ranges.iter().position(|range| range.contains(seed));
+        SEED_COLORS[index.unwrap_or(0) % SEED_COLORS.len()]
+    };
+
+    for (column, layer) in layers.iter().enumerate() {
+        for (range, seed) in &layer.pieces {
+            let y = scale.y(range.start);
+            writeln!(
+                out,
+                "  <rect x=\"{:.1}\" y=\"{y:.1}\" width=\"{COLUMN_WIDTH}\" height=\"{:.1}\" fill=\"{}\"><title>{range:?} from seed {seed}</title></rect>",
+                scale.x(column),
+                (scale.y(range.end) - y).max(1.0),
+                color(seed)
+            )
+            .unwrap();
+        }
+
+        let Some(next) = layers.get(column + 1) else {
+            continue;
+        };
+
+        for piece in &next.pieces {
+            for (from, to) in bands(piece, &layer.pieces) {
+                writeln!(
+                    out,
+                    "  <polygon points=\"{}\" fill=\"{}\" fill-opacity=\"0.6\"/>",
+                    scale.band(column, &from, &to),
+                    color(&piece.1)
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    writeln!(out, "</svg>").unwrap();
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{almanac::Almanac, layers, svg::render, tests::EXAMPLE};
+
+    #[test]
+    fn render_test() {
+        let almanac = EXAMPLE.parse::<Almanac<u64>>().unwrap();
+        let svg = render(&almanac).unwrap();
+        let pieces = layers(&almanac)
+            .unwrap()
+            .iter()
+            .map(|layer| layer.pieces.len())
+            .sum::<usize>();
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("<text x=\"40.0\" y=\"20.0\">seed (2)</text>"));
+        assert!(svg.contains(">location ("));
+        assert_eq!(svg.matches("from seed").count(), pieces);
+        // one band per entry and one per piece after the seeds
+        let entries = almanac.blocks.iter().map(|b| b.ranges.len()).sum::<usize>();
+        assert_eq!(svg.matches("<polygon").count(), entries + pieces - 2);
+    }
+}
@@ -0,0 +1,199 @@
+use std::{fmt, ops::Range};
+
+use crate::{
+    almanac::{Almanac, AlmanacError, Block},
+    number::Number,
+    RangeExt,
+};
+
+/// Two entries of a block, numbered from 1, and the part they share.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlap<N> {
+    pub first: usize,
+    pub second: usize,
+    pub range: Range<N>,
+}
+
+/// What is questionable about a single block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockReport<N> {
+    pub block: String,
+    /// Values with more than one conversion, only the first one is used
+    pub sources: Vec<Overlap<N>>,
+    /// Values more than one source converts to, so the map is not injective
+    pub destinations: Vec<Overlap<N>>,
+    /// Values up to the last mapped one which are passed on unchanged
+    pub gaps: Vec<Range<N>>,
+}
+
+impl<N: Number> BlockReport<N> {
+    pub fn new(block: &Block<N>) -> Self {
+        let sources = block
+            .ranges
+            .iter()
+            .map(|r| r.source.clone())
+            .collect::<Vec<_>>();
+        let destinations = block
+            .ranges
+            .iter()
+            .map(|r| r.dest.clone())
+            .collect::<Vec<_>>();
+
+        BlockReport {
+            block: format!("{}-to-{}", block.from, block.to),
+            sources: overlaps(&sources),
+            destinations: overlaps(&destinations),
+            gaps: gaps(sources),
+        }
+    }
+
+    pub fn has_overlaps(&self) -> bool {
+        !self.sources.is_empty() || !self.destinations.is_empty()
+    }
+}
+
+impl<N: Number> fmt::Display for BlockReport<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.block)?;
+
+        for (kind, overlaps) in [
+            ("sources", &self.sources),
+            ("destinations", &self.destinations),
+        ] {
+            for Overlap {
+                first,
+                second,
+                range,
+            } in overlaps
+            {
+                writeln!(
+                    f,
+                    "\t{kind} of entries {first} and {second} overlap on {range:?}"
+                )?;
+            }
+        }
+
+        if !self.gaps.is_empty() {
+            let gaps = self
+                .gaps
+                .iter()
+                .map(|gap| format!("{gap:?}"))
+                .collect::<Vec<_>>();
+            writeln!(f, "\tunmapped: {}", gaps.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn overlaps<N: Number>(ranges: &[Range<N>]) -> Vec<Overlap<N>> {
+    let mut overlaps = Vec::new();
+
+    for (i, a) in ranges.iter().enumerate() {
+        for (j, b) in ranges.iter().enumerate().skip(i + 1) {
+            if let Some(range) = a.compare(b).get_matching_part() {
+                overlaps.push(Overlap {
+                    first: i + 1,
+                    second: j + 1,
+                    range,
+                });
+            }
+        }
+    }
+
+    overlaps
+}
+
+fn gaps<N: Number>(mut sources: Vec<Range<N>>) -> Vec<Range<N>> {
+    sources.sort_by_key(|source| source.start);
+
+    let mut gaps = Vec::new();
+    let mut covered = N::ZERO;
+
+    for source in sources {
+        if source.start > covered {
+            gaps.push(covered..source.start);
+        }
+        covered = covered.max(source.end);
+    }
+
+    gaps
+}
+
+/// Reports of all blocks, fails if any of them converts a value in more
+/// than one way.
+pub fn validate<N: Number>(almanac: &Almanac<N>) -> Result<Vec<BlockReport<N>>, AlmanacError> {
+    let reports = almanac
+        .blocks
+        .iter()
+        .map(BlockReport::new)
+        .collect::<Vec<_>>();
+
+    match reports.iter().find(|report| !report.sources.is_empty()) {
+        Some(report) => Err(AlmanacError::OverlappingSources(report.block.clone())),
+        None => Ok(reports),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        almanac::{Almanac, AlmanacError, Block},
+        tests::EXAMPLE,
+        validate::{validate, BlockReport, Overlap},
+    };
+
+    #[test]
+    fn example_test() {
+        let almanac = EXAMPLE.parse::<Almanac<u64>>().unwrap();
+        let reports = validate(&almanac).unwrap();
+
+        assert!(reports.iter().all(|report| !report.has_overlaps()));
+        assert_eq!(reports[0].gaps, vec![0..50]);
+        assert_eq!(reports[2].gaps, vec![]);
+    }
+
+    #[test]
+    fn overlap_test() {
+        let block = "seed-to-soil map:\n0 10 5\n3 12 5\n20 0 3"
+            .parse::<Block<u64>>()
+            .unwrap();
+        let report = BlockReport::new(&block);
+
+        assert_eq!(
+            report.sources,
+            vec![Overlap {
+                first: 1,
+                second: 2,
+                range: 12..15
+            }]
+        );
+        assert_eq!(
+            report.destinations,
+            vec![Overlap {
+                first: 1,
+                second: 2,
+                range: 3..5
+            }]
+        );
+        assert_eq!(report.gaps, vec![3..10]);
+        assert_eq!(
+            report.to_string(),
+            "seed-to-soil
+\tsources of entries 1 and 2 overlap on 12..15
+\tdestinations of entries 1 and 2 overlap on 3..5
+\tunmapped: 3..10
+"
+        );
+
+        let almanac = "seeds: 1\n\nseed-to-location map:\n0 10 5\n3 12 5"
+            .parse::<Almanac<u64>>()
+            .unwrap();
+        assert_eq!(
+            validate(&almanac),
+            Err(AlmanacError::OverlappingSources(
+                "seed-to-location".to_string()
+            ))
+        );
+    }
+}
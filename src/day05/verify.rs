@@ -0,0 +1,110 @@
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use rayon::prelude::*;
+
+use crate::{
+    almanac::{Almanac, AlmanacError},
+    lookup,
+    number::Number,
+};
+
+/// Seeds mapped by one task, small enough for the progress to move steadily.
+pub const CHUNK: u32 = 1 << 20;
+
+/// Splits `range` into pieces of at most `chunk` seeds.
+fn chunks<N: Number>(range: Range<N>, chunk: u32) -> Vec<Range<N>> {
+    let mut chunks = Vec::new();
+    let mut start = range.start;
+
+    while start < range.end {
+        let end = match start.checked_add(N::from(chunk)) {
+            Some(end) if end < range.end => end,
+            _ => range.end,
+        };
+        chunks.push(start..end);
+        start = end;
+    }
+
+    chunks
+}
+
+/// Maps every single seed of every seed range through all blocks on all
+/// cores, without ever holding more than a chunk's worth of seeds.
+/// `progress` gets the number of finished and of all chunks after each
+/// chunk. `None` if there are no seeds at all.
+pub fn lowest_location_parallel<N: Number>(
+    almanac: &Almanac<N>,
+    chunk: u32,
+    progress: impl Fn(usize, usize) + Sync,
+) -> Result<Option<N>, AlmanacError> {
+    let chunks = almanac
+        .seed_ranges()?
+        .into_iter()
+        .flat_map(|range| chunks(range, chunk))
+        .collect::<Vec<_>>();
+
+    let done = AtomicUsize::new(0);
+
+    let lowest = chunks
+        .par_iter()
+        .filter_map(|chunk| {
+            let mut lowest = None;
+            let mut seed = chunk.start;
+
+            while seed < chunk.end {
+                let location = almanac
+                    .blocks
+                    .iter()
+                    .fold(seed, |value, block| lookup(&block.ranges, value));
+                lowest = Some(lowest.map_or(location, |lowest: N| lowest.min(location)));
+                seed = seed + N::ONE;
+            }
+
+            progress(done.fetch_add(1, Ordering::Relaxed) + 1, chunks.len());
+            lowest
+        })
+        .min();
+
+    Ok(lowest)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use crate::{
+        almanac::Almanac,
+        get_part_2,
+        tests::EXAMPLE,
+        verify::{chunks, lowest_location_parallel},
+    };
+
+    #[test]
+    fn chunks_test() {
+        assert_eq!(chunks(0u32..7, 3), vec![0..3, 3..6, 6..7]);
+        assert_eq!(
+            chunks(u32::MAX - 4..u32::MAX, 3),
+            vec![u32::MAX - 4..u32::MAX - 1, u32::MAX - 1..u32::MAX]
+        );
+        assert_eq!(chunks(5u64..5, 3), vec![]);
+    }
+
+    #[test]
+    fn parallel_test() {
+        let almanac = EXAMPLE.parse::<Almanac<u64>>().unwrap();
+        let reported = Mutex::new(Vec::new());
+
+        let lowest = lowest_location_parallel(&almanac, 4, |done, total| {
+            reported.lock().unwrap().push((done, total))
+        });
+
+        assert_eq!(lowest, Ok(Some(get_part_2(&almanac).unwrap())));
+
+        let mut reported = reported.into_inner().unwrap();
+        reported.sort();
+        assert_eq!(reported, (1..=8).map(|done| (done, 8)).collect::<Vec<_>>());
+    }
+}
@@ -1,91 +1,253 @@
-use std::fs;
+use std::{fmt, fs, process};
 
-fn get_numbers(line: &str, is_part_two: bool) -> Vec<i64> {
-    let mut numbers: Vec<i64> = Vec::new();
+#[derive(Debug, PartialEq, Eq)]
+enum ParseError {
+    MissingLine(&'static str),
+    StrayCharacter {
+        line: &'static str,
+        c: char,
+    },
+    InvalidNumber(String),
+    NoNumbers(&'static str),
+    CountMismatch {
+        times: usize,
+        distances: usize,
+    },
+    /// A non-empty line after the distance line
+    ExtraLine(String),
+}
 
-    let mut buf: String = String::new();
-    for c in line.chars() {
-        if c.is_ascii_digit() {
-            buf.push(c);
-            continue;
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingLine(label) => write!(f, "missing line starting with {label}"),
+            ParseError::StrayCharacter { line, c } => {
+                write!(f, "unexpected character '{c}' in {line} line")
+            }
+            ParseError::InvalidNumber(n) => write!(f, "invalid number {n}"),
+            ParseError::NoNumbers(label) => write!(f, "no numbers after {label}"),
+            ParseError::CountMismatch { times, distances } => {
+                write!(f, "{times} times but {distances} distances")
+            }
+            ParseError::ExtraLine(line) => write!(f, "unexpected line after distances: {line}"),
         }
+    }
+}
 
-        if buf.len() > 0 && !is_part_two {
-            let i: i64 = buf.parse().unwrap();
-            numbers.push(i);
-            buf.clear();
-        }
+/// The digit groups after `label`, which may only be separated by spaces.
+fn get_groups<'a>(line: Option<&'a str>, label: &'static str) -> Result<Vec<&'a str>, ParseError> {
+    let numbers = line
+        .and_then(|line| line.trim().strip_prefix(label))
+        .ok_or(ParseError::MissingLine(label))?;
+
+    if let Some(c) = numbers
+        .chars()
+        .find(|c| !c.is_ascii_digit() && !c.is_whitespace())
+    {
+        return Err(ParseError::StrayCharacter { line: label, c });
     }
 
-    if buf.len() > 0 {
-        let i: i64 = buf.parse().unwrap();
-        numbers.push(i);
+    let groups = numbers.split_whitespace().collect::<Vec<_>>();
+    if groups.is_empty() {
+        return Err(ParseError::NoNumbers(label));
     }
 
-    return numbers;
+    Ok(groups)
 }
 
-fn get_fastest(time: i64, distance: i64) -> Vec<i64> {
-    let mut better: Vec<i64> = Vec::new();
+/// The digit groups of the time and the distance line, as many of each.
+fn get_lines(input: &str) -> Result<(Vec<&str>, Vec<&str>), ParseError> {
+    let mut lines = input.lines().filter(|line| !line.trim().is_empty());
 
-    for i in 0..time + 1 {
-        let d = i * (time - i);
-        if d > distance {
-            better.push(i);
-        }
+    let times = get_groups(lines.next(), "Time:")?;
+    let distances = get_groups(lines.next(), "Distance:")?;
+
+    if let Some(line) = lines.next() {
+        return Err(ParseError::ExtraLine(line.trim().to_string()));
     }
 
-    return better;
+    if times.len() != distances.len() {
+        return Err(ParseError::CountMismatch {
+            times: times.len(),
+            distances: distances.len(),
+        });
+    }
+
+    Ok((times, distances))
 }
 
-struct Stat {
-    time: i64,
-    distance: i64,
+fn parse_number(n: &str) -> Result<u64, ParseError> {
+    n.parse()
+        .map_err(|_| ParseError::InvalidNumber(n.to_string()))
+}
+
+/// Every digit group is a number of its own.
+fn parse_spaced(groups: &[&str]) -> Result<Vec<u64>, ParseError> {
+    groups.iter().map(|group| parse_number(group)).collect()
 }
 
-fn part_one(lines: &Vec<&str>) -> i64 {
-    let mut numbers: Vec<Vec<i64>> = Vec::new();
+/// All digit groups of a line are one number, the spaces being bad kerning.
+fn parse_kerned(groups: &[&str]) -> Result<u64, ParseError> {
+    parse_number(&groups.concat())
+}
+
+/// One race per column, for part one.
+fn parse_races(input: &str) -> Result<Vec<Stat>, ParseError> {
+    let (times, distances) = get_lines(input)?;
 
-    for line in lines {
-        numbers.push(get_numbers(line, false));
+    let times = parse_spaced(&times)?;
+    let distances = parse_spaced(&distances)?;
+
+    Ok(times
+        .into_iter()
+        .zip(distances)
+        .map(|(time, distance)| Stat { time, distance })
+        .collect())
+}
+
+/// The single race of part two.
+fn parse_race(input: &str) -> Result<Stat, ParseError> {
+    let (times, distances) = get_lines(input)?;
+
+    Ok(Stat {
+        time: parse_kerned(&times)?,
+        distance: parse_kerned(&distances)?,
+    })
+}
+
+/// The lowest and the highest hold time beating `distance`, `None` if none
+/// does. The distance travelled grows up to half of `time` and is symmetric
+/// around it, so the lowest one is searched for in the first half and the
+/// highest one mirrors it.
+fn winning_holds(time: u64, distance: u64) -> Option<(u64, u64)> {
+    // a product too large for u64 beats every record
+    let beats = |hold: u64| {
+        hold.checked_mul(time - hold)
+            .is_none_or(|travelled| travelled > distance)
+    };
+
+    let (mut low, mut high) = (0, time / 2);
+    if !beats(high) {
+        return None;
     }
 
-    let mut stats: Vec<Stat> = Vec::new();
-    for i in 0..numbers[0].len() {
-        let stat: Stat = Stat {
-            time: numbers[0][i],
-            distance: numbers[1][i],
-        };
-        stats.push(stat);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if beats(mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
     }
 
-    let mut sum: i64 = 1;
+    Some((low, time - low))
+}
 
-    for stat in stats {
-        let better = get_fastest(stat.time, stat.distance);
+/// How many hold times beat `distance`.
+fn get_fastest(time: u64, distance: u64) -> u64 {
+    winning_holds(time, distance).map_or(0, |(min, max)| max - min + 1)
+}
 
-        sum *= better.len() as i64;
-    }
+#[derive(Debug, PartialEq, Eq)]
+struct Stat {
+    time: u64,
+    distance: u64,
+}
+
+/// How a race can be won, taken from the hold times [`winning_holds`] finds.
+#[derive(Debug, PartialEq, Eq)]
+struct Margin {
+    ways: u64,
+    min_hold: u64,
+    max_hold: u64,
+    /// The lowest hold time reaching `best_distance`
+    optimal_hold: u64,
+    /// Wide enough for any hold time, unlike u64
+    best_distance: u128,
+    /// How far `best_distance` beats the record
+    margin: u128,
+    /// Hold times right outside the winning ones which exactly match the
+    /// record
+    ties: Vec<u64>,
+}
+
+/// `None` if the record cannot be beaten.
+fn get_margin(stat: &Stat) -> Option<Margin> {
+    let (min_hold, max_hold) = winning_holds(stat.time, stat.distance)?;
+    let distance = |hold: u64| hold as u128 * (stat.time - hold) as u128;
 
+    let optimal_hold = stat.time / 2;
+    let best_distance = distance(optimal_hold);
 
-    return sum;
+    let ties = [min_hold.checked_sub(1), Some(max_hold + 1)]
+        .into_iter()
+        .flatten()
+        .filter(|hold| *hold <= stat.time && distance(*hold) == stat.distance as u128)
+        .collect();
+
+    Some(Margin {
+        ways: max_hold - min_hold + 1,
+        min_hold,
+        max_hold,
+        optimal_hold,
+        best_distance,
+        margin: best_distance - stat.distance as u128,
+        ties,
+    })
 }
 
-fn part_two(lines: &Vec<&str>) -> i64 {
-    let mut numbers: Vec<Vec<i64>> = Vec::new();
+fn part_one(races: &[Stat]) -> u64 {
+    let mut sum: u64 = 1;
 
-    for line in lines {
-        numbers.push(get_numbers(line, true));
+    for stat in races {
+        sum *= get_fastest(stat.time, stat.distance);
     }
 
-    let stat: Stat = Stat {
-        time: numbers[0][0],
-        distance: numbers[1][0],
-    };
+    sum
+}
+
+fn part_two(race: &Stat) -> u64 {
+    get_fastest(race.time, race.distance)
+}
+
+fn print_report(name: &str, stats: &[Stat]) {
+    println!("{name}");
+    println!("\tRace\tTime\tRecord\tWays\tMin\tMax\tOptimal\tBest\tMargin\tTies");
+
+    for (race, stat) in stats.iter().enumerate() {
+        let race = race + 1;
+
+        let Some(m) = get_margin(stat) else {
+            println!(
+                "\t{race}\t{}\t{}\t0\t-\t-\t-\t-\t-\t-",
+                stat.time, stat.distance
+            );
+            continue;
+        };
 
-    let better = get_fastest(stat.time, stat.distance);
+        let ties = m
+            .ties
+            .iter()
+            .map(|hold| hold.to_string())
+            .collect::<Vec<_>>();
 
-    return better.len() as i64;
+        println!(
+            "\t{race}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            stat.time,
+            stat.distance,
+            m.ways,
+            m.min_hold,
+            m.max_hold,
+            m.optimal_hold,
+            m.best_distance,
+            m.margin,
+            if ties.is_empty() {
+                "-".to_string()
+            } else {
+                ties.join(",")
+            }
+        );
+    }
 }
 
 fn main() {
@@ -98,11 +260,145 @@ fn main() {
         Err(e) => panic!("Read file: {}", e),
     };
 
-    let lines = contents.lines().collect();
+    let (races, race) =
+        match parse_races(&contents).and_then(|races| Ok((races, parse_race(&contents)?))) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        };
 
-    let sum_one = part_one(&lines);
-    let sum_two = part_two(&lines);
+    let sum_one = part_one(&races);
+    let sum_two = part_two(&race);
 
     println!("PartOne:\t{sum_one}");
     println!("PartTwo:\t{sum_two}");
+
+    print_report("PartOne", &races);
+    print_report("PartTwo", &[race]);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        get_fastest, get_margin, parse_race, parse_races, part_one, part_two, Margin, ParseError,
+        Stat,
+    };
+
+    const EXAMPLE: &str = "Time:      7  15   30\nDistance:  9  40  200\n";
+
+    #[test]
+    fn parts_test() {
+        assert_eq!(part_one(&parse_races(EXAMPLE).unwrap()), 288);
+        assert_eq!(part_two(&parse_race(EXAMPLE).unwrap()), 71503);
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(
+            parse_race(EXAMPLE),
+            Ok(Stat {
+                time: 71530,
+                distance: 940200
+            })
+        );
+        assert_eq!(parse_races(EXAMPLE).unwrap().len(), 3);
+
+        assert_eq!(
+            parse_races("Time: 7 15\nDistance: 9 40 200"),
+            Err(ParseError::CountMismatch {
+                times: 2,
+                distances: 3
+            })
+        );
+        assert_eq!(
+            parse_race("Time: 7 1x5\nDistance: 9 40"),
+            Err(ParseError::StrayCharacter {
+                line: "Time:",
+                c: 'x'
+            })
+        );
+        assert_eq!(
+            parse_races("Time: 7\nDistance: -9"),
+            Err(ParseError::StrayCharacter {
+                line: "Distance:",
+                c: '-'
+            })
+        );
+        assert_eq!(
+            parse_races("Distance: 9\nTime: 7"),
+            Err(ParseError::MissingLine("Time:"))
+        );
+        assert_eq!(
+            parse_races("Time: 7\nDistance: 9\n\nTime: 8\n"),
+            Err(ParseError::ExtraLine("Time: 8".to_string()))
+        );
+        assert_eq!(
+            parse_races("Time:\nDistance: 9"),
+            Err(ParseError::NoNumbers("Time:"))
+        );
+        assert_eq!(
+            parse_race("Time: 99999999999 99999999999\nDistance: 1 1"),
+            Err(ParseError::InvalidNumber(
+                "9999999999999999999999".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn margin_test() {
+        assert_eq!(
+            get_margin(&Stat {
+                time: 30,
+                distance: 200
+            }),
+            Some(Margin {
+                ways: 9,
+                min_hold: 11,
+                max_hold: 19,
+                optimal_hold: 15,
+                best_distance: 225,
+                margin: 25,
+                ties: vec![10, 20],
+            })
+        );
+
+        let odd = get_margin(&Stat {
+            time: 7,
+            distance: 9,
+        })
+        .unwrap();
+        assert_eq!((odd.min_hold, odd.max_hold, odd.optimal_hold), (2, 5, 3));
+        assert_eq!(odd.best_distance, 12);
+        assert_eq!(odd.ties, vec![]);
+
+        let unbeatable = Stat {
+            time: 4,
+            distance: 4,
+        };
+        assert_eq!(get_margin(&unbeatable), None);
+    }
+
+    #[test]
+    fn fastest_test() {
+        for (time, distance) in [(7, 9), (15, 40), (30, 200), (4, 4), (1, 0), (0, 0)] {
+            let expected = (0..=time)
+                .filter(|hold| hold * (time - hold) > distance)
+                .count();
+            assert_eq!(get_fastest(time, distance), expected as u64);
+        }
+
+        // from a hold time of 2 on the distance no longer fits into u64
+        assert_eq!(get_fastest(u64::MAX, u64::MAX), u64::MAX - 3);
+
+        let huge = get_margin(&Stat {
+            time: u64::MAX,
+            distance: u64::MAX - 1,
+        })
+        .unwrap();
+        assert_eq!((huge.min_hold, huge.max_hold), (2, u64::MAX - 2));
+        assert_eq!(huge.optimal_hold, u64::MAX / 2);
+        assert_eq!(huge.ties, vec![1, u64::MAX - 1]);
+    }
 }
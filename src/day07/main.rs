@@ -1,86 +1,39 @@
-use std::{cmp::Ordering, collections::HashMap, fs};
-
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Card {
-    TWO,
-    THREE,
-    FOUR,
-    FIVE,
-    SIX,
-    SEVEN,
-    EIGHT,
-    NINE,
-    T,
-    J,
-    Q,
-    K,
-    A,
+use std::{collections::HashMap, fs, process};
+
+use aoc::camel::{self, ParseError, Rank, Ranking};
+
+aoc::cards! {
+    TWO = '2',
+    THREE = '3',
+    FOUR = '4',
+    FIVE = '5',
+    SIX = '6',
+    SEVEN = '7',
+    EIGHT = '8',
+    NINE = '9',
+    T = 'T',
+    J = 'J',
+    Q = 'Q',
+    K = 'K',
+    A = 'A',
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
-enum Rank {
-    HighCard,
-    OnePair,
-    TwoPair,
-    ThreeOfKind,
-    FullHouse,
-    FourOfKind,
-    FiveOfKind,
-}
-
-#[derive(Debug)]
-struct Hand {
-    cards: Vec<Card>,
-    bid: i64,
-    rank: Rank,
-}
-
-impl Eq for Hand {}
-
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let cmp = self.rank.cmp(&other.rank);
-        if cmp != Ordering::Equal {
-            return cmp;
-        }
-
-        for (i, _) in self.cards.iter().enumerate() {
-            let card_cmp = self.cards[i].cmp(&other.cards[i]);
-
-            if card_cmp == Ordering::Equal {
-                continue;
-            }
-
-            return card_cmp;
-        }
-        panic!("Cards are equal")
-    }
-}
-
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl Ranking for Card {
+    fn rank(cards: &[Self]) -> Rank {
+        get_rank(cards)
     }
 }
 
-impl PartialEq for Hand {
-    fn eq(&self, other: &Self) -> bool {
-        self.rank == other.rank
-    }
-}
+type Hand = camel::Hand<Card>;
 
-fn get_rank(hand: &Hand) -> Rank {
+fn get_rank(cards: &[Card]) -> Rank {
     let mut card_count: HashMap<&Card, u8> = HashMap::new();
 
-    hand.cards.iter().for_each(|x| {
-        if !card_count.contains_key(x) {
-            card_count.insert(x, 1);
-        } else {
-            card_count.entry(x).and_modify(|u| *u += 1);
-        }
+    cards.iter().for_each(|x| {
+        *card_count.entry(x).or_insert(0) += 1;
     });
 
-    for (_, i) in &card_count {
+    for i in card_count.values() {
         if *i == 5 {
             return Rank::FiveOfKind;
         }
@@ -104,61 +57,26 @@ fn get_rank(hand: &Hand) -> Rank {
     });
 
     match pair_count {
-        1 => return Rank::OnePair,
-        2 => return Rank::TwoPair,
-        _ => (),
+        1 => Rank::OnePair,
+        2 => Rank::TwoPair,
+        _ => Rank::HighCard,
     }
-
-    return Rank::HighCard;
 }
 
-fn check_game(hands: &mut Vec<Hand>) {
+fn check_game(hands: &mut [Hand]) {
     hands.iter_mut().for_each(|h| {
-        let rank = get_rank(h);
+        let rank = get_rank(&h.cards);
         h.rank = rank;
     });
 
     hands.sort();
 }
 
-fn parse_hand(line: &&str) -> Hand {
-    let parts: Vec<&str> = line.split(" ").collect();
-    let bid: i64 = parts[parts.len() - 1].parse().unwrap();
-
-    let cards: Vec<Card> = parts[0]
-        .chars()
-        .map(|c| match c {
-            'A' => Card::A,
-            'K' => Card::K,
-            'Q' => Card::Q,
-            'J' => Card::J,
-            'T' => Card::T,
-            '9' => Card::NINE,
-            '8' => Card::EIGHT,
-            '7' => Card::SEVEN,
-            '6' => Card::SIX,
-            '5' => Card::FIVE,
-            '4' => Card::FOUR,
-            '3' => Card::THREE,
-            '2' => Card::TWO,
-            _ => panic!("Invalid card"),
-        })
-        .collect();
-
-    Hand {
-        cards,
-        bid,
-        rank: Rank::HighCard,
-    }
+fn parse_hands(input: &str) -> Result<Vec<Hand>, ParseError> {
+    input.lines().map(str::parse).collect()
 }
 
-fn part_one(lines: &Vec<&str>) -> i64 {
-    let mut hands: Vec<Hand> = Vec::new();
-
-    for line in lines {
-        hands.push(parse_hand(line));
-    }
-
+fn part_one(mut hands: Vec<Hand>) -> i64 {
     check_game(&mut hands);
 
     let mut sum = 0;
@@ -167,7 +85,7 @@ fn part_one(lines: &Vec<&str>) -> i64 {
         sum += (rank as i64 + 1) * h.bid;
     });
 
-    return sum;
+    sum
 }
 
 fn main() {
@@ -178,9 +96,50 @@ fn main() {
 
     let contents = fs::read_to_string(file_path).expect("Read file");
 
-    let lines = contents.lines().collect();
+    let hands = match parse_hands(&contents) {
+        Ok(hands) => hands,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    };
 
-    let sum_one = part_one(&lines);
+    let sum_one = part_one(hands);
 
     println!("PartOne:\t{sum_one}");
 }
+
+#[cfg(test)]
+mod tests {
+    use aoc::camel::Rank;
+
+    use crate::{parse_hands, part_one, Card, Hand};
+
+    const EXAMPLE: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    #[test]
+    fn part_one_test() {
+        assert_eq!(part_one(parse_hands(EXAMPLE).unwrap()), 6440);
+    }
+
+    #[test]
+    fn roundtrip_test() {
+        for line in EXAMPLE.lines() {
+            assert_eq!(line.parse::<Hand>().unwrap().to_string(), line);
+        }
+
+        let hand = "32T3K 765".parse::<Hand>().unwrap();
+        assert_eq!(
+            hand.cards,
+            vec![Card::THREE, Card::TWO, Card::T, Card::THREE, Card::K]
+        );
+        assert_eq!(hand.bid, 765);
+        assert_eq!(hand.rank, Rank::OnePair);
+        assert_eq!("9".parse::<Card>(), Ok(Card::NINE));
+        assert_eq!(Card::NINE.to_string(), "9");
+    }
+}
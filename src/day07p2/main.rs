@@ -1,111 +1,44 @@
-use std::{cmp::Ordering, collections::HashMap, fs};
-
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    env, fs, process,
+};
+
+use aoc::{
+    alternates::Alternates,
+    camel::{self, ParseError, Rank, Ranking},
+};
 use itertools::Itertools;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Card {
-    J,
-    TWO,
-    THREE,
-    FOUR,
-    FIVE,
-    SIX,
-    SEVEN,
-    EIGHT,
-    NINE,
-    T,
-    Q,
-    K,
-    A,
-}
-
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
-enum Rank {
-    HighCard,
-    OnePair,
-    TwoPair,
-    ThreeOfKind,
-    FullHouse,
-    FourOfKind,
-    FiveOfKind,
-}
-
-#[derive(Debug)]
-struct Hand {
-    cards: Vec<Card>,
-    bid: i64,
-    rank: Rank,
-}
-
-impl Eq for Hand {}
-
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let cmp = self.rank.cmp(&other.rank);
-        if cmp != Ordering::Equal {
-            return cmp;
-        }
-
-        for (i, _) in self.cards.iter().enumerate() {
-            let card_cmp = self.cards[i].cmp(&other.cards[i]);
-
-            if card_cmp == Ordering::Equal {
-                continue;
-            }
-
-            return card_cmp;
-        }
-        panic!("Cards are equal")
-    }
+aoc::cards! {
+    J = 'J',
+    TWO = '2',
+    THREE = '3',
+    FOUR = '4',
+    FIVE = '5',
+    SIX = '6',
+    SEVEN = '7',
+    EIGHT = '8',
+    NINE = '9',
+    T = 'T',
+    Q = 'Q',
+    K = 'K',
+    A = 'A',
 }
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+/// Ranked with jokers as in part two.
+impl Ranking for Card {
+    fn rank(cards: &[Self]) -> Rank {
+        get_rank_part_two(cards)
     }
 }
 
-impl PartialEq for Hand {
-    fn eq(&self, other: &Self) -> bool {
-        self.rank == other.rank
-    }
-}
+type Hand = camel::Hand<Card>;
 
-fn parse_hand(line: &&str) -> Hand {
-    let parts: Vec<&str> = line.split(" ").collect();
-    let bid: i64 = parts[parts.len() - 1].parse().unwrap();
-
-    let cards: Vec<Card> = parts[0]
-        .chars()
-        .map(|c| match c {
-            'A' => Card::A,
-            'K' => Card::K,
-            'Q' => Card::Q,
-            'J' => Card::J,
-            'T' => Card::T,
-            '9' => Card::NINE,
-            '8' => Card::EIGHT,
-            '7' => Card::SEVEN,
-            '6' => Card::SIX,
-            '5' => Card::FIVE,
-            '4' => Card::FOUR,
-            '3' => Card::THREE,
-            '2' => Card::TWO,
-            _ => panic!("Invalid card"),
-        })
-        .collect();
-
-    Hand {
-        cards,
-        bid,
-        rank: Rank::HighCard,
-    }
-}
-
-fn get_rank_part_two(cards: &Vec<Card>) -> Rank {
+fn get_rank_part_two(cards: &[Card]) -> Rank {
     let mut card_count: HashMap<&Card, usize> = cards.iter().counts();
 
-    let joker_count = card_count.get(&Card::J).unwrap_or(&0).clone();
+    let joker_count = *card_count.get(&Card::J).unwrap_or(&0);
     if joker_count > 0 {
         let mut highest_card: Option<&Card> = None;
         let mut highest_count: &usize = &0;
@@ -116,16 +49,20 @@ fn get_rank_part_two(cards: &Vec<Card>) -> Rank {
             }
         }
 
-        if highest_card != None {
+        if let Some(highest_card) = highest_card {
             card_count
-                .entry(highest_card.unwrap())
+                .entry(highest_card)
                 .and_modify(|x| *x += joker_count);
 
             card_count.remove(&Card::J);
         }
     }
 
-    for (_, i) in &card_count {
+    rank_from_counts(&card_count)
+}
+
+fn rank_from_counts(card_count: &HashMap<&Card, usize>) -> Rank {
+    for i in card_count.values() {
         if *i == 5 {
             return Rank::FiveOfKind;
         }
@@ -149,72 +86,80 @@ fn get_rank_part_two(cards: &Vec<Card>) -> Rank {
     });
 
     match pair_count {
-        1 => return Rank::OnePair,
-        2 => return Rank::TwoPair,
-        0 => (),
+        1 => Rank::OnePair,
+        2 => Rank::TwoPair,
+        0 => Rank::HighCard,
         _ => panic!("multiple pairs"),
     }
-
-    return Rank::HighCard;
 }
 
-fn part_two(lines: &Vec<&str>) -> i64 {
-    let mut hands: Vec<Hand> = Vec::new();
+/// The rank of `cards` with every card standing for itself, jokers
+/// included.
+fn get_rank(cards: &[Card]) -> Rank {
+    rank_from_counts(&cards.iter().counts())
+}
 
-    for line in lines {
-        let mut hand = parse_hand(line);
-        hand.rank = get_rank_part_two(&hand.cards);
-        hands.push(hand);
+/// The highest rank `cards` can reach when every card `wild` accepts may be
+/// replaced by any card it doesn't accept, together with the first hand
+/// reaching it. Tries every substitution, so it is exponential in the number
+/// of wild cards, but doesn't rely on how the ranks are built like
+/// [`get_rank_part_two`] does.
+fn best_hand(cards: &[Card], wild: impl Fn(&Card) -> bool) -> (Rank, Vec<Card>) {
+    let substitutes = Card::ALL
+        .into_iter()
+        .filter(|card| !wild(card))
+        .collect::<Vec<_>>();
+    let wilds = cards.iter().positions(&wild).collect::<Vec<_>>();
+
+    if wilds.is_empty() || substitutes.is_empty() {
+        return (get_rank(cards), cards.to_vec());
     }
 
+    itertools::repeat_n(substitutes.iter(), wilds.len())
+        .multi_cartesian_product()
+        .map(|choice| {
+            let mut hand = cards.to_vec();
+            for (&i, &&card) in wilds.iter().zip(&choice) {
+                hand[i] = card;
+            }
+            (get_rank(&hand), hand)
+        })
+        .reduce(|best, next| if next.0 > best.0 { next } else { best })
+        .unwrap()
+}
+
+fn part_two(input: &str) -> Result<i64, ParseError> {
+    let mut hands = input
+        .lines()
+        .map(str::parse)
+        .collect::<Result<Vec<Hand>, _>>()?;
+
     hands.sort();
 
-    hands
+    Ok(hands
         .iter()
         .enumerate()
-        .fold(0, |sum, (rank, h)| sum + (rank + 1) as i64 * h.bid)
+        .fold(0, |sum, (rank, h)| sum + (rank + 1) as i64 * h.bid))
 }
 
-fn char_to_card(c: &char) -> Card {
-    match c {
-        'A' => Card::A,
-        'K' => Card::K,
-        'Q' => Card::Q,
-        'J' => Card::J,
-        'T' => Card::T,
-        '9' => Card::NINE,
-        '8' => Card::EIGHT,
-        '7' => Card::SEVEN,
-        '6' => Card::SIX,
-        '5' => Card::FIVE,
-        '4' => Card::FOUR,
-        '3' => Card::THREE,
-        '2' => Card::TWO,
-        _ => panic!("Invalid card"),
-    }
-}
-
-fn parsing(hand: &Vec<Card>) -> String {
+fn parsing(hand: &[Card]) -> String {
     let mut cards_count = hand.iter().counts();
 
     let j_card = cards_count.get(&Card::J).cloned();
 
-    match j_card {
-        Some(value) => {
-            if value == 5 {
-                return "5".to_string();
-            }
+    if let Some(value) = j_card {
+        if value == 5 {
+            return "5".to_string();
+        }
 
-            cards_count.remove(&Card::J).unwrap();
+        cards_count.remove(&Card::J).unwrap();
 
-            let card = cards_count
-                .iter()
-                .max_by(|(_, a), (_, b)| a.cmp(&b))
-                .unwrap();
+        let card = cards_count
+            .iter()
+            .max_by(|(_, a), (_, b)| a.cmp(b))
+            .unwrap();
 
-            cards_count.entry(card.0).and_modify(|val| *val += value);
-        }
-        None => (),
+        cards_count.entry(card.0).and_modify(|val| *val += value);
     }
 
     cards_count
@@ -224,19 +169,29 @@ fn parsing(hand: &Vec<Card>) -> String {
         .join("")
 }
 
-fn part_two_prettier(lines: &Vec<&str>) -> i64 {
-    lines
-        .iter()
-        .map(|line| match line.split_once(" ") {
+fn part_two_prettier(input: &str) -> Result<i64, ParseError> {
+    let hands = input
+        .lines()
+        .map(|line| match line.split_once(' ') {
             Some((hand, bid)) => {
                 let hand = hand
                     .chars()
-                    .map(|c| char_to_card(&c))
-                    .collect::<Vec<Card>>();
-                (hand, bid.parse::<i64>().unwrap())
+                    .map(Card::try_from)
+                    .collect::<Result<Vec<Card>, _>>()?;
+                if hand.len() != 5 {
+                    return Err(ParseError::CardCount(line.to_string()));
+                }
+                let bid = bid
+                    .parse::<i64>()
+                    .map_err(|_| ParseError::InvalidBid(bid.to_string()))?;
+                Ok((hand, bid))
             }
-            None => panic!("Invalid line {line}"),
+            None => Err(ParseError::MissingBid(line.to_string())),
         })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(hands
+        .into_iter()
         .map(|(hand, bid)| {
             let rank = match parsing(&hand).as_str() {
                 "5" => 6,
@@ -258,7 +213,140 @@ fn part_two_prettier(lines: &Vec<&str>) -> i64 {
         )
         .enumerate()
         .map(|(i, (_, bid, _))| (i + 1) as i64 * bid)
-        .sum()
+        .sum())
+}
+
+/// Prints the best substitution of the jokers of every hand holding any,
+/// fails if the greedy [`get_rank_part_two`] misses it for any of them.
+fn print_best(input: &str) -> Result<bool, ParseError> {
+    let mut optimal = true;
+
+    for line in input.lines() {
+        let hand = line.parse::<Hand>()?;
+        if !hand.cards.contains(&Card::J) {
+            continue;
+        }
+
+        let (rank, best) = best_hand(&hand.cards, |card| *card == Card::J);
+        let greedy = if rank == hand.rank {
+            String::new()
+        } else {
+            optimal = false;
+            format!("\tgreedy: {}", hand.rank)
+        };
+        println!(
+            "{} -> {}\t{rank}{greedy}",
+            hand.cards.iter().join(""),
+            best.iter().join("")
+        );
+    }
+
+    Ok(optimal)
+}
+
+/// How the hands of an input rank under one set of rules.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Distribution {
+    /// Hands per rank, in the order of [`Rank::NAMES`]
+    hands: [usize; 7],
+    bids: [i64; 7],
+    /// Pairs of hands with the same rank by the first card they differ in,
+    /// the last entry counting identical hands no card order can break
+    ties: [usize; 6],
+}
+
+impl Distribution {
+    fn new(hands: &[Hand], rank: impl Fn(&[Card]) -> Rank) -> Self {
+        let mut distribution = Distribution::default();
+        let mut groups: BTreeMap<Rank, Vec<&[Card]>> = BTreeMap::new();
+
+        for hand in hands {
+            let rank = rank(&hand.cards);
+            distribution.hands[rank as usize] += 1;
+            distribution.bids[rank as usize] += hand.bid;
+            groups.entry(rank).or_default().push(&hand.cards);
+        }
+
+        // which card decides does not depend on the order of the cards
+        for group in groups.values() {
+            for (a, b) in group.iter().tuple_combinations() {
+                let card = a.iter().zip(b.iter()).take_while(|(a, b)| a == b).count();
+                distribution.ties[card] += 1;
+            }
+        }
+
+        distribution
+    }
+}
+
+/// Hands whose rank jokers raised, by their rank without and with jokers.
+fn joker_gains(hands: &[Hand]) -> BTreeMap<(Rank, Rank), usize> {
+    let mut gains = BTreeMap::new();
+
+    for hand in hands {
+        let without = get_rank(&hand.cards);
+        if without != hand.rank {
+            *gains.entry((without, hand.rank)).or_insert(0) += 1;
+        }
+    }
+
+    gains
+}
+
+fn print_stats(input: &str) -> Result<(), ParseError> {
+    let hands = input
+        .lines()
+        .map(str::parse)
+        .collect::<Result<Vec<Hand>, _>>()?;
+
+    let rulesets = [
+        ("part one", Distribution::new(&hands, get_rank)),
+        ("part two", Distribution::new(&hands, get_rank_part_two)),
+    ];
+
+    println!(
+        "{:<16} {:>8} {:>12} {:>8} {:>12}",
+        "rank", "part one", "bids", "part two", "bids"
+    );
+    for (i, (_, name)) in Rank::NAMES.iter().enumerate() {
+        println!(
+            "{name:<16} {:>8} {:>12} {:>8} {:>12}",
+            rulesets[0].1.hands[i],
+            rulesets[0].1.bids[i],
+            rulesets[1].1.hands[i],
+            rulesets[1].1.bids[i]
+        );
+    }
+
+    let gains = joker_gains(&hands);
+    println!();
+    println!("jokers raised {} hands", gains.values().sum::<usize>());
+    for ((without, with), count) in gains {
+        println!("\t{without} -> {with}\t{count}");
+    }
+
+    for (name, distribution) in &rulesets {
+        let (identical, ties) = distribution.ties.split_last().unwrap();
+        let ties = ties
+            .iter()
+            .enumerate()
+            .map(|(card, count)| format!("card {}: {count}", card + 1))
+            .join(", ");
+
+        println!();
+        println!("{name} ties broken by {ties}");
+        if *identical > 0 {
+            println!("{name} has {identical} pairs of identical hands");
+        }
+    }
+
+    Ok(())
+}
+
+fn part_two_alternates() -> Alternates<'static, Result<i64, ParseError>> {
+    Alternates::new("PartTwo")
+        .register("part_two", part_two)
+        .register("part_two_prettier", part_two_prettier)
 }
 
 fn main() {
@@ -269,14 +357,152 @@ fn main() {
 
     let contents = fs::read_to_string(file_path).expect("Read file");
 
-    let lines = contents.lines().collect();
+    match env::args().nth(1).as_deref() {
+        Some("best") => match print_best(&contents) {
+            Ok(true) => return,
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        },
+        Some("stats") => match print_stats(&contents) {
+            Ok(()) => return,
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(1);
+            }
+        },
+        _ => (),
+    }
 
-    let sum_two = part_two(&lines);
-    let sum_two_prettier = part_two_prettier(&lines);
+    let sum_two = match part_two_alternates().run(&contents) {
+        Ok(Ok(sum)) => sum,
+        Ok(Err(e)) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+        Err(divergence) => {
+            eprint!("{divergence}");
+            process::exit(1);
+        }
+    };
 
     println!("PartTwo:\t\t{sum_two}");
-    println!("PartTwoPrettier:\t{sum_two_prettier}");
 
     assert_eq!(251135960, sum_two);
-    assert_eq!(251135960, sum_two_prettier);
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use aoc::camel::{ParseError, Rank};
+
+    use crate::{
+        best_hand, get_rank, get_rank_part_two, joker_gains, part_two_alternates, Card,
+        Distribution, Hand,
+    };
+
+    const EXAMPLE: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    #[test]
+    fn part_two_test() {
+        assert_eq!(part_two_alternates().check(EXAMPLE), Ok(5905));
+    }
+
+    #[test]
+    fn roundtrip_test() {
+        for line in EXAMPLE.lines() {
+            assert_eq!(line.parse::<Hand>().unwrap().to_string(), line);
+        }
+
+        let hand = "KTJJT 220".parse::<Hand>().unwrap();
+        assert_eq!(
+            hand.cards,
+            vec![Card::K, Card::T, Card::J, Card::J, Card::T]
+        );
+        assert_eq!(hand.rank, Rank::FourOfKind);
+        assert_eq!(hand.rank.to_string(), "Four of a kind");
+    }
+
+    #[test]
+    fn parse_error_test() {
+        assert_eq!(
+            part_two_alternates().check("KTJJT 220\nKTJ1T 220"),
+            Err(ParseError::InvalidCard("1".to_string()))
+        );
+    }
+
+    #[test]
+    fn best_hand_test() {
+        let cards = "KTJJT 220".parse::<Hand>().unwrap().cards;
+        assert_eq!(
+            best_hand(&cards, |card| *card == Card::J),
+            (
+                Rank::FourOfKind,
+                vec![Card::K, Card::T, Card::T, Card::T, Card::T]
+            )
+        );
+
+        // with the tens wild as well, all five cards can become kings
+        let (rank, best) = best_hand(&cards, |card| matches!(card, Card::J | Card::T));
+        assert_eq!(rank, Rank::FiveOfKind);
+        assert_eq!(best, vec![Card::K; 5]);
+
+        let cards = "23456 1".parse::<Hand>().unwrap().cards;
+        assert_eq!(
+            best_hand(&cards, |_| false),
+            (Rank::HighCard, cards.clone())
+        );
+    }
+
+    #[test]
+    fn greedy_is_optimal_test() {
+        // the rank doesn't depend on the order of the cards
+        for cards in Card::ALL.into_iter().combinations_with_replacement(5) {
+            assert_eq!(
+                get_rank_part_two(&cards),
+                best_hand(&cards, |card| *card == Card::J).0,
+                "{}",
+                cards.iter().join("")
+            );
+        }
+    }
+
+    #[test]
+    fn stats_test() {
+        let hands = EXAMPLE
+            .lines()
+            .map(|line| line.parse::<Hand>().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            Distribution::new(&hands, get_rank),
+            Distribution {
+                hands: [0, 1, 2, 2, 0, 0, 0],
+                bids: [0, 765, 248, 1167, 0, 0, 0],
+                ties: [1, 1, 0, 0, 0, 0],
+            }
+        );
+        assert_eq!(
+            Distribution::new(&hands, get_rank_part_two),
+            Distribution {
+                hands: [0, 1, 1, 0, 0, 3, 0],
+                bids: [0, 765, 28, 0, 0, 1387, 0],
+                ties: [3, 0, 0, 0, 0, 0],
+            }
+        );
+        assert_eq!(
+            joker_gains(&hands).into_iter().collect::<Vec<_>>(),
+            vec![
+                ((Rank::TwoPair, Rank::FourOfKind), 1),
+                ((Rank::ThreeOfKind, Rank::FourOfKind), 2)
+            ]
+        );
+    }
 }
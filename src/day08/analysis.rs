@@ -0,0 +1,262 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write,
+};
+
+use crate::{Cycle, Network, NetworkError};
+
+/// Nodes which are used as a left or right target but never defined.
+pub fn dead_nodes<'a>(network: &Network<'a>) -> BTreeSet<&'a str> {
+    network
+        .nodes
+        .values()
+        .flat_map(|(left, right)| [*left, *right])
+        .filter(|node| !network.nodes.contains_key(node))
+        .collect()
+}
+
+pub fn self_loops<'a>(network: &Network<'a>) -> BTreeSet<&'a str> {
+    network
+        .nodes
+        .iter()
+        .filter(|(node, (left, right))| left == *node || right == *node)
+        .map(|(node, _)| *node)
+        .collect()
+}
+
+/// Every node reachable from `start` when the instructions are ignored,
+/// `start` included.
+pub fn reachable<'a>(network: &Network<'a>, start: &'a str) -> BTreeSet<&'a str> {
+    let mut seen = BTreeSet::from([start]);
+    let mut stack = vec![start];
+
+    while let Some(node) = stack.pop() {
+        let Some((left, right)) = network.nodes.get(node) else {
+            continue;
+        };
+
+        for next in [*left, *right] {
+            if seen.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+
+    seen
+}
+
+struct Tarjan<'a, 'n> {
+    network: &'n Network<'a>,
+    index: HashMap<&'a str, usize>,
+    low_link: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: BTreeSet<&'a str>,
+    components: Vec<Vec<&'a str>>,
+}
+
+impl<'a> Tarjan<'a, '_> {
+    fn enter(&mut self, node: &'a str) {
+        let index = self.index.len();
+        self.index.insert(node, index);
+        self.low_link.insert(node, index);
+        self.stack.push(node);
+        self.on_stack.insert(node);
+    }
+
+    /// Depth first search from `root`. Kept iterative so that long chains of
+    /// nodes can't overflow the call stack, each frame holding a node and how
+    /// many of its targets were looked at.
+    fn visit(&mut self, root: &'a str) {
+        self.enter(root);
+        let mut frames = vec![(root, 0)];
+
+        while let Some((node, visited)) = frames.last_mut() {
+            let node = *node;
+            let (left, right) = self.network.nodes[node];
+
+            if let Some(&next) = [left, right].get(*visited) {
+                *visited += 1;
+
+                if !self.network.nodes.contains_key(next) {
+                    continue;
+                }
+
+                if !self.index.contains_key(next) {
+                    self.enter(next);
+                    frames.push((next, 0));
+                } else if self.on_stack.contains(next) {
+                    let low = self.low_link[node].min(self.index[next]);
+                    self.low_link.insert(node, low);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                let low = self.low_link[parent].min(self.low_link[node]);
+                self.low_link.insert(parent, low);
+            }
+
+            if self.low_link[node] == self.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort();
+                self.components.push(component);
+            }
+        }
+    }
+}
+
+/// Strongly connected components of the defined nodes, each sorted and the
+/// list ordered by size, largest first.
+pub fn strongly_connected_components<'a>(network: &Network<'a>) -> Vec<Vec<&'a str>> {
+    let mut tarjan = Tarjan {
+        network,
+        index: HashMap::new(),
+        low_link: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: BTreeSet::new(),
+        components: Vec::new(),
+    };
+
+    let mut nodes = network.nodes.keys().copied().collect::<Vec<_>>();
+    nodes.sort();
+
+    for node in nodes {
+        if !tarjan.index.contains_key(node) {
+            tarjan.visit(node);
+        }
+    }
+
+    let mut components = tarjan.components;
+    components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    components
+}
+
+/// Rejects networks in which a walk from any of `starts` could run into a
+/// dead node. Dead nodes no start can reach don't matter.
+pub fn validate(network: &Network, starts: &[&str]) -> Result<(), NetworkError> {
+    let dead = dead_nodes(network);
+
+    match starts
+        .iter()
+        .flat_map(|start| reachable(network, start))
+        .find(|node| dead.contains(node))
+    {
+        Some(node) => Err(NetworkError::MissingNode(node.to_string())),
+        None => Ok(()),
+    }
+}
+
+/// The least common multiple of the first end hits only lines the ghosts up
+/// if every ghost is on an end node exactly at the multiples of its first
+/// hit. Past the offset hits repeat with the loop length, so if that is a
+/// multiple of the first hit too, checking one pass through the loop is
+/// enough.
+pub fn lcm_applies(cycle: &Cycle) -> bool {
+    let Some(&first) = cycle.ends.first() else {
+        return false;
+    };
+    let length = cycle.nodes.len() as i64;
+
+    first > 0
+        && length % first == 0
+        && cycle
+            .ends
+            .iter()
+            .copied()
+            .eq((first..cycle.offset + length).step_by(first as usize))
+}
+
+pub fn report(network: &Network, cycles: Option<&[Cycle]>) -> String {
+    let mut out = String::new();
+
+    let components = strongly_connected_components(network);
+    let self_loops = self_loops(network);
+    let trivial = components
+        .iter()
+        .filter(|c| c.len() == 1 && !self_loops.contains(c[0]))
+        .count();
+
+    writeln!(out, "Nodes:\t\t{}", network.nodes.len()).unwrap();
+    writeln!(out, "Instructions:\t{}", network.instructions.len()).unwrap();
+    writeln!(
+        out,
+        "Components:\t{} ({} single nodes)",
+        components.len(),
+        trivial
+    )
+    .unwrap();
+    for component in components.iter().filter(|c| c.len() > 1) {
+        writeln!(out, "\t{} nodes: {}", component.len(), component.join(" ")).unwrap();
+    }
+
+    writeln!(
+        out,
+        "Self loops:\t{}",
+        self_loops.iter().copied().collect::<Vec<_>>().join(" ")
+    )
+    .unwrap();
+
+    let dead = dead_nodes(network);
+    writeln!(
+        out,
+        "Dead nodes:\t{}",
+        dead.iter().copied().collect::<Vec<_>>().join(" ")
+    )
+    .unwrap();
+
+    let mut starts = network
+        .nodes
+        .keys()
+        .filter(|k| k.ends_with('A'))
+        .copied()
+        .collect::<Vec<_>>();
+    starts.sort();
+
+    writeln!(out, "Reachable:").unwrap();
+    for start in starts {
+        let reachable = reachable(network, start);
+        let ends = reachable
+            .iter()
+            .filter(|n| n.ends_with('Z'))
+            .copied()
+            .collect::<Vec<_>>();
+
+        writeln!(
+            out,
+            "\t{start}: {} nodes, Z nodes: {}",
+            reachable.len(),
+            ends.join(" ")
+        )
+        .unwrap();
+    }
+
+    if let Some(cycles) = cycles {
+        writeln!(out, "Ghost cycles:").unwrap();
+        for cycle in cycles {
+            writeln!(
+                out,
+                "\t{}: offset {}, length {}, Z at {:?}, lcm {}",
+                cycle.start,
+                cycle.offset,
+                cycle.nodes.len(),
+                cycle.ends,
+                if lcm_applies(cycle) {
+                    "applies"
+                } else {
+                    "fails"
+                }
+            )
+            .unwrap();
+        }
+    }
+
+    out
+}
@@ -0,0 +1,70 @@
+use std::{collections::BTreeSet, fmt::Write};
+
+use crate::{Cycle, Network};
+
+const CYCLE_COLORS: [&str; 6] = [
+    "red",
+    "darkorange",
+    "purple",
+    "forestgreen",
+    "brown",
+    "magenta",
+];
+
+/// Renders the network as a DOT digraph. `A` and `Z` nodes are filled, the
+/// walked `path` is drawn in bold blue and every ghost cycle gets its own
+/// dashed color on top of the plain `L`/`R` edges.
+pub fn render(network: &Network, path: &[&str], cycles: &[Cycle]) -> String {
+    let mut out = String::new();
+
+    let mut nodes = network.nodes.iter().collect::<Vec<_>>();
+    nodes.sort();
+
+    writeln!(out, "digraph network {{").unwrap();
+    writeln!(out, "    node [shape=circle, fontname=monospace];").unwrap();
+
+    for (node, _) in &nodes {
+        if node.ends_with('A') {
+            writeln!(out, "    \"{node}\" [style=filled, fillcolor=palegreen];").unwrap();
+        } else if node.ends_with('Z') {
+            writeln!(out, "    \"{node}\" [style=filled, fillcolor=salmon];").unwrap();
+        }
+    }
+
+    for (node, (left, right)) in &nodes {
+        if left == right {
+            writeln!(out, "    \"{node}\" -> \"{left}\" [label=\"LR\"];").unwrap();
+        } else {
+            writeln!(out, "    \"{node}\" -> \"{left}\" [label=\"L\"];").unwrap();
+            writeln!(out, "    \"{node}\" -> \"{right}\" [label=\"R\"];").unwrap();
+        }
+    }
+
+    let path_edges = path
+        .iter()
+        .zip(path.iter().skip(1))
+        .collect::<BTreeSet<_>>();
+
+    for (from, to) in path_edges {
+        writeln!(out, "    \"{from}\" -> \"{to}\" [color=blue, penwidth=2];").unwrap();
+    }
+
+    for (cycle, color) in cycles.iter().zip(CYCLE_COLORS.iter().cycle()) {
+        let next = cycle.nodes.iter().cycle().skip(1);
+        // a loop runs over (node, instruction) states, so nodes and edges repeat
+        let edges = cycle.nodes.iter().zip(next).collect::<BTreeSet<_>>();
+
+        for (from, to) in edges {
+            writeln!(
+                out,
+                "    \"{from}\" -> \"{to}\" [color={color}, style=dashed, tooltip=\"{}\"];",
+                cycle.start
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(out, "}}").unwrap();
+
+    out
+}
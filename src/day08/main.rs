@@ -1,59 +1,587 @@
-use std::{collections::HashMap, fs};
+use std::{collections::HashMap, env, fmt, fs, io, process};
 
-fn part_one(contents: &String) -> i64 {
-    let (instr, maps) = contents.split_once("\n\n").unwrap();
+mod analysis;
+mod dot;
+mod matcher;
+mod replay;
 
-    let map = maps
-        .split("\n")
+use matcher::NodeMatcher;
+use replay::{Replay, Step};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Direction {
+    Left,
+    Right,
+}
+
+impl TryFrom<char> for Direction {
+    type Error = NetworkError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'L' => Ok(Direction::Left),
+            'R' => Ok(Direction::Right),
+            val => Err(NetworkError::InvalidInstruction(val)),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Left => write!(f, "L"),
+            Direction::Right => write!(f, "R"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum NetworkError {
+    InvalidInstruction(char),
+    InvalidLine(String),
+    NoInstructions,
+    MissingNode(String),
+    InvalidPattern(String),
+    InvalidTrace(String),
+    WriteTrace {
+        path: String,
+        error: String,
+    },
+    IrregularCycle(String),
+    /// First end hits whose least common multiple doesn't fit into an i64
+    LcmOverflow(Vec<i64>),
+    Unreachable {
+        target: String,
+        steps: i64,
+        loop_length: i64,
+    },
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkError::InvalidInstruction(c) => write!(f, "invalid instruction {c}"),
+            NetworkError::InvalidLine(line) => write!(f, "invalid node line \"{line}\""),
+            NetworkError::NoInstructions => write!(f, "no instructions given"),
+            NetworkError::MissingNode(node) => write!(f, "node {node} is not defined"),
+            NetworkError::InvalidPattern(e) => write!(f, "invalid node pattern: {e}"),
+            NetworkError::InvalidTrace(line) => write!(f, "invalid trace line \"{line}\""),
+            NetworkError::WriteTrace { path, error } => {
+                write!(f, "could not write trace to {path}: {error}")
+            }
+            NetworkError::IrregularCycle(start) => write!(
+                f,
+                "walk from {start} is not on an end node at exactly the multiples of its first hit"
+            ),
+            NetworkError::LcmOverflow(steps) => {
+                write!(f, "least common multiple of {steps:?} overflows")
+            }
+            NetworkError::Unreachable {
+                target,
+                steps,
+                loop_length,
+            } => write!(
+                f,
+                "{target} unreachable after {steps} steps, loop of length {loop_length}"
+            ),
+        }
+    }
+}
+
+struct Network<'a> {
+    instructions: Vec<Direction>,
+    nodes: HashMap<&'a str, (&'a str, &'a str)>,
+}
+
+impl<'a> Network<'a> {
+    fn next(&self, node: &'a str, direction: Direction) -> Result<&'a str, NetworkError> {
+        let (left, right) = self
+            .nodes
+            .get(node)
+            .ok_or_else(|| NetworkError::MissingNode(node.to_string()))?;
+
+        match direction {
+            Direction::Left => Ok(left),
+            Direction::Right => Ok(right),
+        }
+    }
+}
+
+fn parse_network(contents: &str) -> Result<Network<'_>, NetworkError> {
+    let (instr, maps) = contents
+        .split_once("\n\n")
+        .ok_or_else(|| NetworkError::InvalidLine(contents.to_string()))?;
+
+    let instructions = instr
+        .trim()
+        .chars()
+        .map(Direction::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if instructions.is_empty() {
+        return Err(NetworkError::NoInstructions);
+    }
+
+    let nodes = maps
+        .lines()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
         .map(|s| {
-            let (key, fields) = s.split_once(" = ").unwrap();
-            let (left, right) = fields.split_once(", ").unwrap();
-            let left = left.replace("(", "");
-            let right = right.replace(")", "");
+            let invalid = || NetworkError::InvalidLine(s.to_string());
+            let (key, fields) = s.split_once(" = ").ok_or_else(invalid)?;
+            let (left, right) = fields
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .split_once(", ")
+                .ok_or_else(invalid)?;
 
-            (key, (left, right))
+            Ok((key, (left, right)))
         })
-        .collect::<HashMap<_, _>>();
-    let (mut key, mut value) = map.get_key_value("AAA").unwrap();
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    Ok(Network {
+        instructions,
+        nodes,
+    })
+}
+
+/// Walks from `start` until a node matching `end` is hit and returns the
+/// number of steps taken. Since the walk is deterministic on `(node,
+/// instruction index)`, seeing such a state twice means `end` can never be
+/// reached.
+///
+/// If given a `trace`, every state visited is pushed onto it. If `end` is
+/// unreachable the trace stops at the first repeated state, so it shows
+/// where the walk enters its loop.
+fn record_walk<'a>(
+    network: &Network<'a>,
+    start: &'a str,
+    end: &NodeMatcher,
+    mut trace: Option<&mut Vec<Step<&'a str>>>,
+) -> Result<i64, NetworkError> {
+    if !network.nodes.contains_key(start) {
+        return Err(NetworkError::MissingNode(start.to_string()));
+    }
+    if !network.nodes.keys().any(|node| end.matches(node)) {
+        return Err(NetworkError::MissingNode(end.to_string()));
+    }
+
+    let mut seen: HashMap<(&str, usize), i64> = HashMap::new();
+    let mut node = start;
+    let mut steps = 0;
+
+    loop {
+        let index = steps as usize % network.instructions.len();
+        let direction = network.instructions[index];
+
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.push(Step {
+                step: steps,
+                index,
+                direction,
+                node,
+            });
+        }
+
+        if end.matches(node) {
+            return Ok(steps);
+        }
+
+        if let Some(first) = seen.insert((node, index), steps) {
+            return Err(NetworkError::Unreachable {
+                target: end.to_string(),
+                steps,
+                loop_length: steps - first,
+            });
+        }
+
+        node = network.next(node, direction)?;
+        steps += 1;
+    }
+}
+
+fn walk(network: &Network, start: &str, end: &NodeMatcher) -> Result<i64, NetworkError> {
+    record_walk(network, start, end, None)
+}
+
+/// Every node [`walk`] visits, both ends included.
+fn walk_path<'a>(
+    network: &Network<'a>,
+    start: &'a str,
+    end: &NodeMatcher,
+) -> Result<Vec<&'a str>, NetworkError> {
+    let mut trace = Vec::new();
+    record_walk(network, start, end, Some(&mut trace))?;
+
+    Ok(trace.into_iter().map(|step| step.node).collect())
+}
 
-    let mut sum = 0;
-    for i in instr.chars().into_iter().cycle() {
-        if *key == "ZZZ" {
-            break;
+/// The defined nodes matching `start`, sorted.
+fn starts<'a>(network: &Network<'a>, start: &NodeMatcher) -> Result<Vec<&'a str>, NetworkError> {
+    let mut starts = network
+        .nodes
+        .keys()
+        .filter(|node| start.matches(node))
+        .copied()
+        .collect::<Vec<_>>();
+    starts.sort();
+
+    if starts.is_empty() {
+        return Err(NetworkError::MissingNode(start.to_string()));
+    }
+
+    Ok(starts)
+}
+
+/// The loop a ghost ends up in when walking from its start node.
+struct Cycle<'a> {
+    start: &'a str,
+    /// Steps taken before the loop is entered
+    offset: i64,
+    /// Nodes on the loop in walk order, the step after the last one is the first one again
+    nodes: Vec<&'a str>,
+    /// Steps at which an end node is visited, all below `offset + nodes.len()`
+    ends: Vec<i64>,
+}
+
+fn ghost_cycle<'a>(
+    network: &Network<'a>,
+    start: &'a str,
+    end: &NodeMatcher,
+) -> Result<Cycle<'a>, NetworkError> {
+    let mut seen: HashMap<(&str, usize), i64> = HashMap::new();
+    let mut path: Vec<&str> = Vec::new();
+    let mut node = start;
+    let mut steps = 0;
+
+    loop {
+        let index = steps as usize % network.instructions.len();
+
+        if let Some(first) = seen.insert((node, index), steps) {
+            let ends = path
+                .iter()
+                .enumerate()
+                .filter(|(_, n)| end.matches(n))
+                .map(|(i, _)| i as i64)
+                .collect();
+
+            return Ok(Cycle {
+                start,
+                offset: first,
+                nodes: path.split_off(first as usize),
+                ends,
+            });
+        }
+
+        path.push(node);
+        node = network.next(node, network.instructions[index])?;
+        steps += 1;
+    }
+}
+
+fn ghost_cycles<'a>(
+    network: &Network<'a>,
+    start: &NodeMatcher,
+    end: &NodeMatcher,
+) -> Result<Vec<Cycle<'a>>, NetworkError> {
+    starts(network, start)?
+        .into_iter()
+        .map(|start| ghost_cycle(network, start, end))
+        .collect()
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// `None` if the result doesn't fit into an i64.
+fn lcm(a: i64, b: i64) -> Option<i64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Walks from every node matching `start` at once and returns the step at
+/// which all of them stand on an `end` node. With a single start this is a
+/// plain walk. With several, the ghosts line up at the least common multiple
+/// of their first hits, as long as each of them is on an end node exactly at
+/// the multiples of its first hit, which [`analysis::lcm_applies`] checks.
+/// The real input is built that way.
+fn solve(network: &Network, start: &NodeMatcher, end: &NodeMatcher) -> Result<i64, NetworkError> {
+    let starts = starts(network, start)?;
+
+    let first_ends = starts
+        .iter()
+        .map(|start| walk(network, start, end))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if starts.len() > 1 && first_ends.iter().any(|steps| *steps != 0) {
+        for start in starts {
+            if !analysis::lcm_applies(&ghost_cycle(network, start, end)?) {
+                return Err(NetworkError::IrregularCycle(start.to_string()));
+            }
         }
+    }
+
+    first_ends
+        .iter()
+        .try_fold(1, |acc, steps| lcm(acc, *steps))
+        .ok_or(NetworkError::LcmOverflow(first_ends))
+}
+
+fn part_one(contents: &str) -> Result<i64, NetworkError> {
+    let network = parse_network(contents)?;
+
+    solve(
+        &network,
+        &NodeMatcher::exact("AAA"),
+        &NodeMatcher::exact("ZZZ"),
+    )
+}
+
+fn part_two(contents: &str) -> Result<i64, NetworkError> {
+    let network = parse_network(contents)?;
+
+    solve(
+        &network,
+        &NodeMatcher::suffix("A"),
+        &NodeMatcher::suffix("Z"),
+    )
+}
+
+/// The network as DOT. Broken networks are drawn as well, leaving out the
+/// path or the ghost cycles which can't be walked, with a warning on stderr.
+fn render_dot(network: &Network) -> String {
+    let path = walk_path(network, "AAA", &NodeMatcher::exact("ZZZ")).unwrap_or_else(|e| {
+        eprintln!("Not highlighting a path: {e}");
+        Vec::new()
+    });
+
+    let starts = starts(network, &NodeMatcher::suffix("A")).unwrap_or_else(|e| {
+        eprintln!("Not highlighting ghost cycles: {e}");
+        Vec::new()
+    });
+    let cycles = starts
+        .into_iter()
+        .filter_map(
+            |start| match ghost_cycle(network, start, &NodeMatcher::suffix("Z")) {
+                Ok(cycle) => Some(cycle),
+                Err(e) => {
+                    eprintln!("Not highlighting the cycle from {start}: {e}");
+                    None
+                }
+            },
+        )
+        .collect::<Vec<_>>();
+
+    dot::render(network, &path, &cycles)
+}
+
+fn print_dot(contents: &str) -> Result<(), NetworkError> {
+    let network = parse_network(contents)?;
+
+    print!("{}", render_dot(&network));
+
+    Ok(())
+}
+
+fn print_analysis(contents: &str) -> Result<(), NetworkError> {
+    let network = parse_network(contents)?;
+
+    let starts = starts(&network, &NodeMatcher::suffix("A"));
+    let cycles = match starts.and_then(|starts| analysis::validate(&network, &starts)) {
+        Ok(()) => Some(ghost_cycles(
+            &network,
+            &NodeMatcher::suffix("A"),
+            &NodeMatcher::suffix("Z"),
+        )?),
+        Err(_) => None,
+    };
+
+    print!("{}", analysis::report(&network, cycles.as_deref()));
+
+    Ok(())
+}
+
+fn print_walk(contents: &str, start: &str, end: &str) -> Result<(), NetworkError> {
+    let network = parse_network(contents)?;
+
+    let start = start.parse::<NodeMatcher>()?;
+    let end = end.parse::<NodeMatcher>()?;
 
-        match i {
-            'L' => (key, value) = map.get_key_value(&value.0.as_str()).unwrap(),
-            'R' => (key, value) = map.get_key_value(&value.1.as_str()).unwrap(),
-            val => panic!("Invalid instruction {val}"),
+    let starts = starts(&network, &start)?;
+
+    for node in starts {
+        println!("{node}:\t{}", walk(&network, node, &end)?);
+    }
+    println!("Together:\t{}", solve(&network, &start, &end)?);
+
+    Ok(())
+}
+
+/// Records the walk of part one and writes it to `out`, also if it never
+/// reaches its end.
+fn print_trace(contents: &str, out: &str) -> Result<(), NetworkError> {
+    let network = parse_network(contents)?;
+
+    let mut trace = Vec::new();
+    let result = record_walk(
+        &network,
+        "AAA",
+        &NodeMatcher::exact("ZZZ"),
+        Some(&mut trace),
+    );
+
+    fs::write(out, replay::write(&trace)).map_err(|e| NetworkError::WriteTrace {
+        path: out.to_string(),
+        error: e.to_string(),
+    })?;
+    println!("Recorded {} steps to {out}", trace.len());
+
+    result.map(|_| ())
+}
+
+fn print_step(replay: &Replay) {
+    if let Some(step) = replay.current() {
+        match replay.note() {
+            Some(note) => println!("{step}\t{note}"),
+            None => println!("{step}"),
         }
+    }
+}
+
+/// Steps through a trace written by [`print_trace`], reading commands from
+/// stdin.
+fn print_replay(contents: &str) -> Result<(), NetworkError> {
+    let mut replay = Replay::new(replay::read(contents)?);
 
-        sum += 1;
+    println!("{} steps recorded", replay.len());
+    if let Some((first, repeat)) = replay.loop_entry() {
+        println!("Loop entered at step {first}, repeated at step {repeat}");
     }
+    print_step(&replay);
 
-    return sum;
+    for line in io::stdin().lines() {
+        let line = line.expect("Read command");
+        let position = replay.position() as i64;
+
+        let count = match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [] | ["s"] => Some(1),
+            ["s", n] => n.parse().ok(),
+            ["b"] => Some(-1),
+            ["b", n] => n.parse::<i64>().ok().map(|n| -n),
+            ["g", n] => n.parse::<i64>().ok().map(|n| n - position),
+            ["l"] => replay
+                .loop_entry()
+                .map(|(first, _)| first as i64 - position),
+            ["q"] => break,
+            _ => None,
+        };
+
+        match count {
+            Some(count) => {
+                replay.step(count);
+                print_step(&replay);
+            }
+            None => eprintln!(
+                "Commands: s [n] steps forward, b [n] back, g <step> seeks, l jumps to the loop, q quits"
+            ),
+        }
+    }
+
+    Ok(())
 }
 
-fn part_two(lines: &String) -> i64 {
-    todo!()
+/// Prints both parts, each on its own so that an error in one of them
+/// doesn't hide the other. Returns whether both succeeded.
+fn print_parts(contents: &str) -> bool {
+    let results = [
+        ("PartOne:\t", part_one(contents)),
+        ("PartTwo:\t", part_two(contents)),
+    ];
+
+    let mut failed = false;
+    for (name, result) in results {
+        match result {
+            Ok(steps) => println!("{name}{steps}"),
+            Err(e) => {
+                eprintln!("{name}{e}");
+                failed = true;
+            }
+        }
+    }
+
+    !failed
 }
 
 fn main() {
-    let file_path = "input/day08.txt";
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let file_path = |i: usize| {
+        args.get(i)
+            .cloned()
+            .unwrap_or("input/day08.txt".to_string())
+    };
+    let read = |path: &str| fs::read_to_string(path).expect("Read file");
+
+    let result = match args.first().map(String::as_str) {
+        Some("dot") => print_dot(&read(&file_path(1))),
+        Some("analyze") => print_analysis(&read(&file_path(1))),
+        Some("walk") => {
+            let (Some(start), Some(end)) = (args.get(1), args.get(2)) else {
+                eprintln!("Usage: day08 walk <start> <end> [file]");
+                eprintln!("Nodes are matched by label (AAA), suffix (*Z) or regex (re:^.AZ$)");
+                process::exit(2);
+            };
+
+            print_walk(&read(&file_path(3)), start, end)
+        }
+        Some("trace") => {
+            let Some(out) = args.get(1) else {
+                eprintln!("Usage: day08 trace <out> [file]");
+                process::exit(2);
+            };
 
-    println!("---------- Day08 ----------");
-    println!("Reading {}", file_path);
+            print_trace(&read(&file_path(2)), out)
+        }
+        Some("replay") => {
+            let Some(trace) = args.get(1) else {
+                eprintln!("Usage: day08 replay <trace>");
+                process::exit(2);
+            };
+
+            print_replay(&read(trace))
+        }
+        _ => {
+            let file_path = file_path(0);
 
-    let contents = fs::read_to_string(file_path).expect("Read file");
+            println!("---------- Day08 ----------");
+            println!("Reading {}", file_path);
 
-    let sum_one = part_one(&contents);
+            if !print_parts(&read(&file_path)) {
+                process::exit(1);
+            }
+            Ok(())
+        }
+    };
 
-    println!("PartOne:\t{sum_one}");
+    if let Err(e) = result {
+        eprintln!("Error:\t{e}");
+        process::exit(1);
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::part_one;
+    use crate::{
+        analysis, dot, ghost_cycles, parse_network, part_one, part_two, print_trace, record_walk,
+        render_dot, replay, solve, walk_path, Direction, NetworkError, NodeMatcher, Replay, Step,
+    };
 
     #[test]
     fn part_one_test() {
@@ -64,6 +592,356 @@ mod tests {
         ZZZ = (ZZZ, ZZZ)"
             .to_string();
 
-        assert_eq!(part_one(&result), 6);
+        assert_eq!(part_one(&result), Ok(6));
+    }
+
+    #[test]
+    fn part_one_unreachable_test() {
+        let result = "LR
+
+        AAA = (BBB, BBB)
+        BBB = (AAA, AAA)
+        ZZZ = (ZZZ, ZZZ)";
+
+        assert_eq!(
+            part_one(result),
+            Err(NetworkError::Unreachable {
+                target: "ZZZ".to_string(),
+                steps: 2,
+                loop_length: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn part_one_missing_nodes_test() {
+        let no_start = "L
+
+        BBB = (ZZZ, ZZZ)
+        ZZZ = (ZZZ, ZZZ)";
+
+        assert_eq!(
+            part_one(no_start),
+            Err(NetworkError::MissingNode("AAA".to_string()))
+        );
+
+        let dangling = "L
+
+        AAA = (CCC, CCC)
+        ZZZ = (ZZZ, ZZZ)";
+
+        assert_eq!(
+            part_one(dangling),
+            Err(NetworkError::MissingNode("CCC".to_string()))
+        );
+
+        // no walk from AAA gets near DDD
+        let unreachable_dead = "L
+
+        AAA = (ZZZ, ZZZ)
+        ZZZ = (ZZZ, ZZZ)
+        QQQ = (DDD, DDD)";
+
+        assert_eq!(part_one(unreachable_dead), Ok(1));
+
+        // DDD is a neighbour of AAA, but the walk only ever turns left
+        let unwalked_dead = "L
+
+        AAA = (ZZZ, DDD)
+        ZZZ = (ZZZ, ZZZ)";
+
+        assert_eq!(part_one(unwalked_dead), Ok(1));
+    }
+
+    const GHOSTS: &str = "LR
+
+        11A = (11B, XXX)
+        11B = (XXX, 11Z)
+        11Z = (11B, XXX)
+        22A = (22B, XXX)
+        22B = (22C, 22C)
+        22C = (22Z, 22Z)
+        22Z = (22B, 22B)
+        XXX = (XXX, XXX)";
+
+    #[test]
+    fn part_two_test() {
+        assert_eq!(part_two(GHOSTS), Ok(6));
+    }
+
+    #[test]
+    fn ghost_cycles_test() {
+        let network = parse_network(GHOSTS).unwrap();
+        let cycles = ghost_cycles(
+            &network,
+            &NodeMatcher::suffix("A"),
+            &NodeMatcher::suffix("Z"),
+        )
+        .unwrap();
+
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(cycles[0].offset, 1);
+        assert_eq!(cycles[0].nodes, vec!["11B", "11Z"]);
+        assert_eq!(cycles[0].ends, vec![2]);
+        assert_eq!(cycles[1].offset, 1);
+        assert_eq!(
+            cycles[1].nodes,
+            vec!["22B", "22C", "22Z", "22B", "22C", "22Z"]
+        );
+        assert_eq!(cycles[1].ends, vec![3, 6]);
+    }
+
+    #[test]
+    fn dot_test() {
+        let network = parse_network(
+            "L
+
+            AAA = (ZZZ, ZZZ)
+            ZZZ = (ZZZ, ZZZ)",
+        )
+        .unwrap();
+        let path = walk_path(&network, "AAA", &NodeMatcher::exact("ZZZ")).unwrap();
+        let cycles = ghost_cycles(
+            &network,
+            &NodeMatcher::suffix("A"),
+            &NodeMatcher::suffix("Z"),
+        )
+        .unwrap();
+
+        let rendered = dot::render(&network, &path, &cycles);
+
+        assert!(rendered.starts_with("digraph network {"));
+        assert!(rendered.contains("\"AAA\" [style=filled, fillcolor=palegreen];"));
+        assert!(rendered.contains("\"ZZZ\" [style=filled, fillcolor=salmon];"));
+        assert!(rendered.contains("\"AAA\" -> \"ZZZ\" [color=blue, penwidth=2];"));
+        assert!(rendered.contains("\"ZZZ\" -> \"ZZZ\" [color=red, style=dashed, tooltip=\"AAA\"];"));
+
+        assert_eq!(render_dot(&network), rendered);
+
+        // neither the path nor the ghost from 11A can be walked
+        let broken = parse_network(
+            "L
+
+            11A = (DDD, DDD)
+            22A = (22Z, 22Z)
+            22Z = (22Z, 22Z)",
+        )
+        .unwrap();
+        let rendered = render_dot(&broken);
+        assert!(rendered.contains("\"11A\" -> \"DDD\" [label=\"LR\"];"));
+        assert!(!rendered.contains("color=blue"));
+        assert!(rendered.contains("\"22Z\" -> \"22Z\" [color=red, style=dashed, tooltip=\"22A\"];"));
+    }
+
+    #[test]
+    fn analysis_test() {
+        let network = parse_network(
+            "L
+
+            AAA = (BBB, CCC)
+            BBB = (AAA, ZZZ)
+            CCC = (DDD, CCC)
+            ZZZ = (ZZZ, ZZZ)",
+        )
+        .unwrap();
+
+        assert_eq!(
+            analysis::strongly_connected_components(&network),
+            vec![vec!["AAA", "BBB"], vec!["CCC"], vec!["ZZZ"]]
+        );
+        assert_eq!(
+            analysis::self_loops(&network)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["CCC", "ZZZ"]
+        );
+        assert_eq!(
+            analysis::dead_nodes(&network)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["DDD"]
+        );
+        assert_eq!(
+            analysis::reachable(&network, "CCC")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["CCC", "DDD"]
+        );
+        assert_eq!(
+            analysis::validate(&network, &["AAA"]),
+            Err(NetworkError::MissingNode("DDD".to_string()))
+        );
+        assert_eq!(analysis::validate(&network, &["ZZZ"]), Ok(()));
+
+        // deep enough to overflow the stack with a recursive search
+        let chain = (0..100_000)
+            .map(|i| format!("N{i} = (N{0}, N{0})", (i + 1) % 100_000))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let input = format!("L\n\n{chain}");
+        let network = parse_network(&input).unwrap();
+        let components = analysis::strongly_connected_components(&network);
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), 100_000);
+    }
+
+    #[test]
+    fn lcm_applies_test() {
+        let network = parse_network(GHOSTS).unwrap();
+        let cycles = ghost_cycles(
+            &network,
+            &NodeMatcher::suffix("A"),
+            &NodeMatcher::suffix("Z"),
+        )
+        .unwrap();
+
+        // 22A is on an end node at 3, 6, 9, ..., so twice per loop
+        assert!(analysis::lcm_applies(&cycles[0]));
+        assert!(analysis::lcm_applies(&cycles[1]));
+    }
+
+    #[test]
+    fn solve_matchers_test() {
+        let network = parse_network(GHOSTS).unwrap();
+        let matcher = |s: &str| s.parse::<NodeMatcher>().unwrap();
+
+        assert_eq!(solve(&network, &matcher("11A"), &matcher("11Z")), Ok(2));
+        assert_eq!(solve(&network, &matcher("*A"), &matcher("*Z")), Ok(6));
+        assert_eq!(
+            solve(&network, &matcher("22A"), &matcher("re:^2.Z$")),
+            Ok(3)
+        );
+        // 22B is on an end node at 2, 5, 8, ... and 22C at 1, 4, 7, ...
+        assert_eq!(
+            solve(&network, &matcher("re:^22[BC]$"), &matcher("*Z")),
+            Err(NetworkError::IrregularCycle("22B".to_string()))
+        );
+        assert_eq!(
+            solve(&network, &matcher("11A"), &matcher("22Z")),
+            Err(NetworkError::Unreachable {
+                target: "22Z".to_string(),
+                steps: 3,
+                loop_length: 2,
+            })
+        );
+        assert_eq!(
+            solve(&network, &matcher("*Q"), &matcher("*Z")),
+            Err(NetworkError::MissingNode("*Q".to_string()))
+        );
+        assert!("re:(".parse::<NodeMatcher>().is_err());
+    }
+
+    /// One ghost per length, each walking a ring of that many nodes and
+    /// standing on its end node at every multiple of it.
+    fn rings(lengths: &[i64]) -> String {
+        let mut input = "L\n".to_string();
+        for length in lengths {
+            let node = |i: i64| match i {
+                0 => format!("{length}A"),
+                i if i == *length => format!("{length}Z"),
+                i => format!("{length}_{i}"),
+            };
+            for i in 0..*length {
+                input += &format!("\n{} = ({1}, {1})", node(i), node(i + 1));
+            }
+            input += &format!("\n{} = ({1}, {1})", node(*length), node(1));
+        }
+        input
+    }
+
+    #[test]
+    fn solve_overflow_test() {
+        let primes = [1009, 1013, 1019, 1021, 1031, 1033, 1039];
+        let matcher = |s: &str| s.parse::<NodeMatcher>().unwrap();
+
+        let input = rings(&primes[..6]);
+        let network = parse_network(&input).unwrap();
+        assert_eq!(
+            solve(&network, &matcher("*A"), &matcher("*Z")),
+            Ok(primes[..6].iter().product())
+        );
+
+        let input = rings(&primes);
+        let network = parse_network(&input).unwrap();
+        assert_eq!(
+            solve(&network, &matcher("*A"), &matcher("*Z")),
+            Err(NetworkError::LcmOverflow(primes.to_vec()))
+        );
+
+        // every ghost already stands on an end node
+        let network = parse_network(GHOSTS).unwrap();
+        assert_eq!(solve(&network, &matcher("*Z"), &matcher("*Z")), Ok(0));
+    }
+
+    #[test]
+    fn replay_test() {
+        let network_input = "LR
+
+            AAA = (BBB, BBB)
+            BBB = (CCC, CCC)
+            CCC = (BBB, BBB)
+            ZZZ = (ZZZ, ZZZ)";
+        let network = parse_network(network_input).unwrap();
+
+        let mut trace = Vec::new();
+        assert_eq!(
+            record_walk(
+                &network,
+                "AAA",
+                &NodeMatcher::exact("ZZZ"),
+                Some(&mut trace)
+            ),
+            Err(NetworkError::Unreachable {
+                target: "ZZZ".to_string(),
+                steps: 3,
+                loop_length: 2,
+            })
+        );
+
+        let written = replay::write(&trace);
+        assert_eq!(
+            written,
+            "0\t0\tL\tAAA\n1\t1\tR\tBBB\n2\t0\tL\tCCC\n3\t1\tR\tBBB\n"
+        );
+        let steps = replay::read(&written).unwrap();
+        assert_eq!(
+            steps[3],
+            Step {
+                step: 3,
+                index: 1,
+                direction: Direction::Right,
+                node: "BBB".to_string(),
+            }
+        );
+
+        let mut replay = Replay::new(steps);
+        assert_eq!(replay.len(), 4);
+        assert_eq!(replay.loop_entry(), Some((1, 3)));
+        assert_eq!(replay.step(2).map(|step| step.node.as_str()), Some("CCC"));
+        assert_eq!(replay.note(), None);
+        assert_eq!(replay.step(-5).map(|step| step.step), Some(0));
+        assert_eq!(replay.seek(10).map(|step| step.step), Some(3));
+        assert_eq!(
+            replay.note(),
+            Some("back at step 1, loop of length 2".to_string())
+        );
+
+        assert_eq!(
+            replay::read("0\t0\tX\tAAA"),
+            Err(NetworkError::InvalidTrace("0\t0\tX\tAAA".to_string()))
+        );
+        assert_eq!(
+            replay::read("0\t0\tL\tAAA\n2\t0\tL\tCCC"),
+            Err(NetworkError::InvalidTrace("2\t0\tL\tCCC".to_string()))
+        );
+        assert_eq!(
+            replay::read("1\t1\tR\tBBB"),
+            Err(NetworkError::InvalidTrace("1\t1\tR\tBBB".to_string()))
+        );
+
+        assert!(matches!(
+            print_trace(network_input, "/nonexistent/trace.tsv"),
+            Err(NetworkError::WriteTrace { .. })
+        ));
     }
 }
@@ -0,0 +1,64 @@
+use std::{fmt, str::FromStr};
+
+use regex::Regex;
+
+use crate::NetworkError;
+
+/// Selects nodes by label. Parsed from `AAA` (exact), `*Z` (suffix, `*` alone
+/// matches every node) or `re:^[AB].Z$` (regex).
+#[derive(Debug, Clone)]
+pub(crate) enum NodeMatcher {
+    Exact(String),
+    Suffix(String),
+    Regex(Regex),
+}
+
+impl NodeMatcher {
+    pub fn exact(label: &str) -> Self {
+        NodeMatcher::Exact(label.to_string())
+    }
+
+    pub fn suffix(suffix: &str) -> Self {
+        NodeMatcher::Suffix(suffix.to_string())
+    }
+
+    pub fn matches(&self, node: &str) -> bool {
+        match self {
+            NodeMatcher::Exact(label) => node == label,
+            NodeMatcher::Suffix(suffix) => node.ends_with(suffix.as_str()),
+            NodeMatcher::Regex(re) => re.is_match(node),
+        }
+    }
+}
+
+impl FromStr for NodeMatcher {
+    type Err = NetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(pattern) = s.strip_prefix("re:") {
+            return Regex::new(pattern)
+                .map(NodeMatcher::Regex)
+                .map_err(|e| NetworkError::InvalidPattern(e.to_string()));
+        }
+
+        if let Some(suffix) = s.strip_prefix('*') {
+            return Ok(NodeMatcher::suffix(suffix));
+        }
+
+        if s.is_empty() {
+            return Err(NetworkError::InvalidPattern("empty node label".to_string()));
+        }
+
+        Ok(NodeMatcher::exact(s))
+    }
+}
+
+impl fmt::Display for NodeMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeMatcher::Exact(label) => write!(f, "{label}"),
+            NodeMatcher::Suffix(suffix) => write!(f, "*{suffix}"),
+            NodeMatcher::Regex(re) => write!(f, "re:{re}"),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use crate::{Direction, NetworkError};
+
+/// A state of a walk: the node stood on after `step` steps and the
+/// instruction taken from it. Written as one tab separated line per step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Step<N> {
+    pub step: i64,
+    /// Index into the instructions, the walk repeats once a node is seen at
+    /// the same index again
+    pub index: usize,
+    pub direction: Direction,
+    pub node: N,
+}
+
+impl<N: fmt::Display> fmt::Display for Step<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}",
+            self.step, self.index, self.direction, self.node
+        )
+    }
+}
+
+impl FromStr for Step<String> {
+    type Err = NetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || NetworkError::InvalidTrace(s.to_string());
+
+        let [step, index, direction, node] = s.split('\t').collect::<Vec<_>>()[..] else {
+            return Err(invalid());
+        };
+
+        let mut direction = direction.chars();
+        let direction = match (direction.next(), direction.next()) {
+            (Some(c), None) => Direction::try_from(c).map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        };
+
+        Ok(Step {
+            step: step.parse().map_err(|_| invalid())?,
+            index: index.parse().map_err(|_| invalid())?,
+            direction,
+            node: node.to_string(),
+        })
+    }
+}
+
+/// Renders a recorded walk, one step per line.
+pub fn write<N: fmt::Display>(steps: &[Step<N>]) -> String {
+    steps.iter().map(|step| format!("{step}\n")).collect()
+}
+
+/// Reads a trace back. The steps have to be numbered from 0 without gaps,
+/// as a step's number is where [`Replay`] looks for it.
+pub fn read(contents: &str) -> Result<Vec<Step<String>>, NetworkError> {
+    let mut steps = Vec::new();
+
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        let step = line.parse::<Step<String>>()?;
+        if step.step != steps.len() as i64 {
+            return Err(NetworkError::InvalidTrace(line.to_string()));
+        }
+        steps.push(step);
+    }
+
+    Ok(steps)
+}
+
+/// A cursor over a recorded walk.
+pub(crate) struct Replay {
+    steps: Vec<Step<String>>,
+    position: usize,
+    /// First step of the loop and the step coming back to it, if the walk
+    /// ran into one
+    loop_entry: Option<(usize, usize)>,
+}
+
+impl Replay {
+    pub fn new(steps: Vec<Step<String>>) -> Self {
+        let mut seen: HashMap<(&str, usize), usize> = HashMap::new();
+        let loop_entry = steps.iter().enumerate().find_map(|(i, step)| {
+            seen.insert((&step.node, step.index), i)
+                .map(|first| (first, i))
+        });
+
+        Replay {
+            steps,
+            position: 0,
+            loop_entry,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn current(&self) -> Option<&Step<String>> {
+        self.steps.get(self.position)
+    }
+
+    pub fn loop_entry(&self) -> Option<(usize, usize)> {
+        self.loop_entry
+    }
+
+    /// Moves to `position`, clamped to the last recorded step.
+    pub fn seek(&mut self, position: usize) -> Option<&Step<String>> {
+        self.position = position.min(self.steps.len().saturating_sub(1));
+        self.current()
+    }
+
+    /// Moves `count` steps forwards, or backwards if negative.
+    pub fn step(&mut self, count: i64) -> Option<&Step<String>> {
+        let position = self.position as i64 + count;
+        self.seek(position.max(0) as usize)
+    }
+
+    /// What is special about the current step, if anything.
+    pub fn note(&self) -> Option<String> {
+        let (first, repeat) = self.loop_entry?;
+
+        if self.position == first {
+            Some(format!("loop entered, repeated at step {repeat}"))
+        } else if self.position == repeat {
+            Some(format!(
+                "back at step {first}, loop of length {}",
+                repeat - first
+            ))
+        } else {
+            None
+        }
+    }
+}
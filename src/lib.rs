@@ -0,0 +1,3 @@
+pub mod alternates;
+pub mod camel;
+pub mod trace;
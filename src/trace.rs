@@ -0,0 +1,203 @@
+use std::{collections::BTreeMap, fmt::Write};
+
+/// How much of a [`Trace`] is recorded and rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    #[default]
+    Quiet,
+    /// Only lines on which the algorithms disagree
+    Disagreements,
+    All,
+}
+
+impl Verbosity {
+    /// `-v` for disagreements, `-vv` for everything.
+    pub fn from_flag(flag: &str) -> Option<Self> {
+        match flag {
+            "-v" => Some(Verbosity::Disagreements),
+            "-vv" => Some(Verbosity::All),
+            _ => None,
+        }
+    }
+}
+
+/// A token an algorithm matched, at its byte position in the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub position: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Cell {
+    tokens: Vec<Token>,
+    value: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Row {
+    text: String,
+    cells: BTreeMap<usize, Cell>,
+}
+
+impl Row {
+    fn disagrees(&self) -> bool {
+        let mut values = self.cells.values().map(|cell| &cell.value);
+        let first = values.next();
+
+        values.any(|value| Some(value) != first)
+    }
+}
+
+/// Per line records of what each algorithm matched and computed, rendered as
+/// one column per algorithm.
+///
+/// With [`Trace::comparing`] and [`Verbosity::Disagreements`], a line is
+/// dropped as soon as all compared algorithms recorded it and agree, so when
+/// they run over the input line by line in lockstep only the disagreeing
+/// lines are kept.
+#[derive(Debug, Default)]
+pub struct Trace {
+    verbosity: Verbosity,
+    algorithms: Vec<String>,
+    /// Whether `algorithms` was given up front, so that a row holding a cell
+    /// for each of them is complete
+    comparing: bool,
+    rows: BTreeMap<usize, Row>,
+}
+
+impl Trace {
+    pub fn new(verbosity: Verbosity) -> Self {
+        Trace {
+            verbosity,
+            ..Default::default()
+        }
+    }
+
+    /// Fixes the algorithms and their column order.
+    pub fn comparing(mut self, algorithms: &[&str]) -> Self {
+        self.algorithms = algorithms.iter().map(|a| a.to_string()).collect();
+        self.comparing = true;
+        self
+    }
+
+    /// Whether anything gets recorded at all, to skip collecting tokens.
+    pub fn enabled(&self) -> bool {
+        self.verbosity > Verbosity::Quiet
+    }
+
+    pub fn record(
+        &mut self,
+        algorithm: &str,
+        line: usize,
+        text: &str,
+        tokens: Vec<Token>,
+        value: Option<impl ToString>,
+    ) {
+        if !self.enabled() {
+            return;
+        }
+
+        let column = match self.algorithms.iter().position(|a| a == algorithm) {
+            Some(column) => column,
+            None => {
+                self.algorithms.push(algorithm.to_string());
+                self.algorithms.len() - 1
+            }
+        };
+
+        let row = self.rows.entry(line).or_default();
+        row.text = text.to_string();
+        row.cells.insert(
+            column,
+            Cell {
+                tokens,
+                value: value.map(|v| v.to_string()),
+            },
+        );
+
+        let complete = self.comparing && row.cells.len() == self.algorithms.len();
+        if complete && self.verbosity == Verbosity::Disagreements && !row.disagrees() {
+            self.rows.remove(&line);
+        }
+    }
+
+    /// Lines currently held.
+    pub fn lines(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Lines on which the recorded values differ.
+    pub fn disagreements(&self) -> usize {
+        self.rows.values().filter(|row| row.disagrees()).count()
+    }
+
+    pub fn render(&self) -> String {
+        let rows = self
+            .rows
+            .iter()
+            .filter(|(_, row)| self.verbosity == Verbosity::All || row.disagrees())
+            .map(|(line, row)| {
+                let mut cells = vec![line.to_string(), row.text.clone()];
+
+                for column in 0..self.algorithms.len() {
+                    cells.push(match row.cells.get(&column) {
+                        Some(cell) => render_cell(cell),
+                        None => String::new(),
+                    });
+                }
+                cells
+            })
+            .collect::<Vec<_>>();
+
+        if rows.is_empty() {
+            return String::new();
+        }
+
+        let mut header = vec!["Line".to_string(), "Input".to_string()];
+        header.extend(self.algorithms.iter().cloned());
+
+        let widths = header
+            .iter()
+            .enumerate()
+            .map(|(i, title)| {
+                rows.iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([title.chars().count()])
+                    .max()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let mut out = String::new();
+        for row in [header].iter().chain(rows.iter()) {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join(" | ");
+
+            writeln!(out, "{}", line.trim_end()).unwrap();
+        }
+
+        out
+    }
+}
+
+fn render_cell(cell: &Cell) -> String {
+    let tokens = cell
+        .tokens
+        .iter()
+        .map(|token| format!("{}@{}", token.text, token.position))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let value = cell.value.as_deref().unwrap_or("-");
+
+    if tokens.is_empty() {
+        value.to_string()
+    } else {
+        format!("{tokens} = {value}")
+    }
+}